@@ -0,0 +1,15 @@
+//! Derived statistics over the loaded profile that back the analysis panels.
+
+pub mod blocking;
+pub mod cgu;
+pub mod density;
+pub mod histogram;
+pub mod idle_gaps;
+pub mod metrics;
+pub mod outliers;
+pub mod phases;
+pub mod queries;
+pub mod query_keys;
+pub mod range;
+pub mod thread_summary;
+pub mod utilization;
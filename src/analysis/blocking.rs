@@ -0,0 +1,275 @@
+//! Matching of `QueryBlocked` intervals with the query execution they wait
+//! on, for parallel frontend profiles.
+
+use crate::Message;
+use crate::data::{EventId, KindInfo, TimelineEvent, thread_name};
+use crate::symbols::{Symbol, Symbols};
+use crate::timeline::format_duration;
+use iced::widget::{Space, checkbox, column, container, row, scrollable, text};
+use iced::{Alignment, Element, Length};
+use std::collections::HashMap;
+
+const QUERY_BLOCKED_EVENT_KIND: &str = "QueryBlocked";
+const QUERY_EVENT_KIND: &str = "Query";
+
+/// Longest list of blocked intervals shown.
+const MAX_ROWS: usize = 200;
+
+#[derive(Debug, Clone, Copy)]
+pub struct BlockedQuery {
+    pub blocked: EventId,
+    /// The execution of the same query on another thread overlapping the
+    /// blocked interval the most, if any.
+    pub executing: Option<EventId>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ThreadBlocking {
+    pub thread_id: u32,
+    pub blocked_ns: u64,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct QueryBlocking {
+    /// Sorted by descending blocked duration.
+    pub blocked: Vec<BlockedQuery>,
+    /// Sorted by descending blocked time.
+    pub threads: Vec<ThreadBlocking>,
+}
+
+type QueryKey<'a> = (Symbol, Option<&'a [Symbol]>);
+
+fn query_key(event: &TimelineEvent) -> QueryKey<'_> {
+    (event.label, event.additional_data.as_deref())
+}
+
+/// Length of the time both events run.
+pub fn overlap_ns(a: &TimelineEvent, b: &TimelineEvent) -> u64 {
+    let start = a.start_ns.max(b.start_ns);
+    let end = (a.start_ns + a.duration_ns).min(b.start_ns + b.duration_ns);
+    end.saturating_sub(start)
+}
+
+impl QueryBlocking {
+    pub fn compute(events: &[TimelineEvent], kinds: &[KindInfo], symbols: &Symbols) -> Self {
+        let kind_index = |name: &str| {
+            kinds
+                .iter()
+                .position(|kind| symbols.resolve(kind.kind) == name)
+                .map(|index| index as u16)
+        };
+        let Some(blocked_kind) = kind_index(QUERY_BLOCKED_EVENT_KIND) else {
+            return QueryBlocking::default();
+        };
+        let query_kind = kind_index(QUERY_EVENT_KIND);
+
+        let mut blocked_by_key: HashMap<QueryKey<'_>, Vec<EventId>> = HashMap::new();
+        for (index, event) in events.iter().enumerate() {
+            if event.kind_index == blocked_kind && !event.is_thread_root {
+                blocked_by_key
+                    .entry(query_key(event))
+                    .or_default()
+                    .push(EventId(index as u32));
+            }
+        }
+
+        let mut executions_by_key: HashMap<QueryKey<'_>, Vec<EventId>> = HashMap::new();
+        for (index, event) in events.iter().enumerate() {
+            if Some(event.kind_index) == query_kind
+                && !event.is_thread_root
+                && let Some(key_blocked) = blocked_by_key.get_key_value(&query_key(event))
+            {
+                executions_by_key
+                    .entry(*key_blocked.0)
+                    .or_default()
+                    .push(EventId(index as u32));
+            }
+        }
+
+        let mut blocked = Vec::new();
+        let mut threads: HashMap<u32, ThreadBlocking> = HashMap::new();
+        for (key, blocked_ids) in &blocked_by_key {
+            let executions = executions_by_key.get(key).map_or(&[][..], Vec::as_slice);
+            for &id in blocked_ids {
+                let event = &events[id.index()];
+                let executing = executions
+                    .iter()
+                    .copied()
+                    .filter(|candidate| events[candidate.index()].thread_id != event.thread_id)
+                    .map(|candidate| (candidate, overlap_ns(event, &events[candidate.index()])))
+                    .filter(|&(_, overlap)| overlap > 0)
+                    .max_by_key(|&(_, overlap)| overlap)
+                    .map(|(candidate, _)| candidate);
+                blocked.push(BlockedQuery {
+                    blocked: id,
+                    executing,
+                });
+                let thread = threads.entry(event.thread_id).or_insert(ThreadBlocking {
+                    thread_id: event.thread_id,
+                    blocked_ns: 0,
+                    count: 0,
+                });
+                thread.blocked_ns += event.duration_ns;
+                thread.count += 1;
+            }
+        }
+
+        blocked.sort_by_key(|query| std::cmp::Reverse(events[query.blocked.index()].duration_ns));
+        let mut threads: Vec<ThreadBlocking> = threads.into_values().collect();
+        threads.sort_by_key(|thread| std::cmp::Reverse(thread.blocked_ns));
+        QueryBlocking { blocked, threads }
+    }
+
+    /// Links touching `event`, either as the blocked or the executing side.
+    pub fn links_of(&self, event: EventId) -> impl Iterator<Item = &BlockedQuery> {
+        self.blocked
+            .iter()
+            .filter(move |query| query.blocked == event || query.executing == Some(event))
+    }
+}
+
+pub struct BlockingViewArgs<'a> {
+    pub blocking: &'a QueryBlocking,
+    pub events: &'a [TimelineEvent],
+    pub symbols: &'a Symbols,
+    pub thread_names: &'a HashMap<u32, String>,
+    pub min_ns: u64,
+    pub show_all_links: bool,
+}
+
+pub fn view<'a>(args: BlockingViewArgs<'a>) -> Element<'a, Message> {
+    let BlockingViewArgs {
+        blocking,
+        events,
+        symbols,
+        thread_names,
+        min_ns,
+        show_all_links,
+    } = args;
+
+    let content: Element<'a, Message> = if blocking.blocked.is_empty() {
+        text("No QueryBlocked events in this profile.")
+            .size(12)
+            .into()
+    } else {
+        let total_ns: u64 = blocking
+            .threads
+            .iter()
+            .map(|thread| thread.blocked_ns)
+            .sum();
+        let matched = blocking
+            .blocked
+            .iter()
+            .filter(|query| query.executing.is_some())
+            .count();
+        let controls = row![
+            checkbox(show_all_links)
+                .label("Draw all blocked-on arrows in the timeline")
+                .size(14)
+                .text_size(12)
+                .on_toggle(Message::ShowAllQueryLinksToggled),
+            Space::new().width(Length::Fill),
+            text(format!(
+                "{} blocked intervals, {} matched, {} blocked in total",
+                blocking.blocked.len(),
+                matched,
+                format_duration(total_ns)
+            ))
+            .size(12),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center);
+
+        let mut thread_rows = column![
+            row![
+                text("Thread").size(12).width(Length::Fixed(80.0)),
+                text("Blocked").size(12).width(Length::Fixed(90.0)),
+                text("Intervals").size(12).width(Length::Fixed(80.0)),
+            ]
+            .spacing(8)
+        ]
+        .spacing(2);
+        for thread in &blocking.threads {
+            thread_rows = thread_rows.push(
+                row![
+                    text(thread_name(thread_names, thread.thread_id))
+                        .size(12)
+                        .width(Length::Fixed(80.0)),
+                    text(format_duration(thread.blocked_ns))
+                        .size(12)
+                        .width(Length::Fixed(90.0)),
+                    text(format!("{}", thread.count))
+                        .size(12)
+                        .width(Length::Fixed(80.0)),
+                ]
+                .spacing(8),
+            );
+        }
+
+        let mut blocked_rows = column![
+            row![
+                text("Blocked").size(12).width(Length::Fixed(80.0)),
+                text("Thread").size(12).width(Length::Fixed(80.0)),
+                text("Query").size(12).width(Length::Fill),
+                text("Executed on").size(12).width(Length::Fixed(80.0)),
+                Space::new().width(Length::Fixed(50.0)),
+            ]
+            .spacing(8)
+        ]
+        .spacing(2);
+        for query in blocking.blocked.iter().take(MAX_ROWS) {
+            let event = &events[query.blocked.index()];
+            let start_ns = event.start_ns.saturating_sub(min_ns);
+            let executed_on = match query.executing {
+                Some(id) => thread_name(thread_names, events[id.index()].thread_id),
+                None => "unmatched".to_string(),
+            };
+            blocked_rows = blocked_rows.push(
+                row![
+                    text(format_duration(event.duration_ns))
+                        .size(12)
+                        .width(Length::Fixed(80.0)),
+                    text(thread_name(thread_names, event.thread_id))
+                        .size(12)
+                        .width(Length::Fixed(80.0)),
+                    text(symbols.resolve(event.label))
+                        .size(12)
+                        .width(Length::Fill),
+                    text(executed_on).size(12).width(Length::Fixed(80.0)),
+                    crate::ui::show_range_button(
+                        start_ns as f64,
+                        (start_ns + event.duration_ns) as f64,
+                        Some(query.blocked),
+                    ),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center),
+            );
+        }
+        if blocking.blocked.len() > MAX_ROWS {
+            let more = blocking.blocked.len() - MAX_ROWS;
+            blocked_rows = blocked_rows.push(text(format!("… {} more", more)).size(12));
+        }
+
+        column![
+            controls,
+            row![
+                scrollable::Scrollable::new(thread_rows)
+                    .width(Length::Fixed(280.0))
+                    .height(Length::Fill),
+                scrollable::Scrollable::new(blocked_rows).height(Length::Fill),
+            ]
+            .spacing(16)
+            .height(Length::Fill),
+        ]
+        .spacing(8)
+        .into()
+    };
+
+    container(column![content].padding(10))
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(crate::ui::panel_style)
+        .into()
+}
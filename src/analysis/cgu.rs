@@ -0,0 +1,409 @@
+//! Backend timings per codegen unit.
+//!
+//! Codegen and LLVM events (`codegen_module`, `LLVM_module_optimize`,
+//! `LLVM_module_codegen_emit_obj`, ...) record the name of their CGU as the
+//! first `additional_data` entry.
+
+use crate::Message;
+use crate::color_rules::RuleColors;
+use crate::data::{EventId, FileData, TimelineEvent, thread_name};
+use crate::symbols::{Symbol, Symbols};
+use crate::timeline::{color_from_label, format_duration};
+use crate::ui::shade;
+use iced::mouse;
+use iced::widget::canvas::{self, Action, Canvas, Geometry, Program};
+use iced::widget::{column, container, row, scrollable, text};
+use iced::{Color, Element, Event, Length, Point, Rectangle, Renderer, Size, Theme};
+use std::collections::HashMap;
+
+const ROW_HEIGHT: f32 = 16.0;
+/// Width of the CGU names left of the chart.
+const NAME_WIDTH: f32 = 220.0;
+/// Longest list of CGUs shown below the chart.
+const MAX_ROWS: usize = 500;
+
+fn is_cgu_stage(label: &str) -> bool {
+    label.starts_with("codegen_module") || label.starts_with("LLVM_")
+}
+
+#[derive(Debug, Clone)]
+pub struct Cgu {
+    pub name: Symbol,
+    /// Ordered by start time, enclosing stages first.
+    pub stages: Vec<EventId>,
+    pub start_ns: u64,
+    pub end_ns: u64,
+    /// Time covered by the outermost stages, summed over threads.
+    pub total_ns: u64,
+    /// Distinct threads the stages ran on.
+    pub threads: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CguTimings {
+    /// Ordered by the start of their first stage.
+    pub cgus: Vec<Cgu>,
+    pub start_ns: u64,
+    pub end_ns: u64,
+}
+
+impl CguTimings {
+    pub fn compute(data: &FileData) -> Self {
+        let events = &data.events;
+        let mut is_stage: HashMap<Symbol, bool> = HashMap::new();
+        let mut stages_by_cgu: HashMap<Symbol, Vec<EventId>> = HashMap::new();
+        for (index, event) in events.iter().enumerate() {
+            let Some(&name) = event
+                .additional_data
+                .as_deref()
+                .and_then(|data| data.first())
+            else {
+                continue;
+            };
+            let stage = *is_stage
+                .entry(event.label)
+                .or_insert_with(|| is_cgu_stage(data.symbols.resolve(event.label)));
+            if stage {
+                stages_by_cgu
+                    .entry(name)
+                    .or_default()
+                    .push(EventId(index as u32));
+            }
+        }
+
+        let mut cgus: Vec<Cgu> = stages_by_cgu
+            .into_iter()
+            .map(|(name, mut stages)| {
+                stages.sort_by_key(|id| {
+                    let event = &events[id.index()];
+                    (
+                        event.start_ns,
+                        std::cmp::Reverse(event.start_ns + event.duration_ns),
+                    )
+                });
+                // End of the outermost stage running on each thread.
+                let mut outer_end: HashMap<u32, u64> = HashMap::new();
+                let mut total_ns = 0;
+                let mut end_ns = 0;
+                for id in &stages {
+                    let event = &events[id.index()];
+                    let event_end = event.start_ns + event.duration_ns;
+                    end_ns = end_ns.max(event_end);
+                    let thread_end = outer_end.entry(event.thread_id).or_default();
+                    if event.start_ns >= *thread_end {
+                        total_ns += event.duration_ns;
+                        *thread_end = event_end;
+                    }
+                }
+                Cgu {
+                    name,
+                    start_ns: events[stages[0].index()].start_ns,
+                    end_ns,
+                    total_ns,
+                    threads: outer_end.len(),
+                    stages,
+                }
+            })
+            .collect();
+        cgus.sort_by_key(|cgu| cgu.start_ns);
+
+        CguTimings {
+            start_ns: cgus.iter().map(|cgu| cgu.start_ns).min().unwrap_or(0),
+            end_ns: cgus.iter().map(|cgu| cgu.end_ns).max().unwrap_or(0),
+            cgus,
+        }
+    }
+}
+
+struct GanttProgram<'a> {
+    timings: &'a CguTimings,
+    events: &'a [TimelineEvent],
+    symbols: &'a Symbols,
+    rule_colors: &'a RuleColors,
+    thread_names: &'a HashMap<u32, String>,
+    min_ns: u64,
+}
+
+#[derive(Default)]
+struct GanttState {
+    hovered: Option<EventId>,
+}
+
+impl GanttProgram<'_> {
+    fn ns_per_pixel(&self, bounds: Rectangle) -> f64 {
+        let span = self
+            .timings
+            .end_ns
+            .saturating_sub(self.timings.start_ns)
+            .max(1);
+        span as f64 / (bounds.width - NAME_WIDTH).max(1.0) as f64
+    }
+
+    fn stage_rect(&self, bounds: Rectangle, row: usize, event: &TimelineEvent) -> Rectangle {
+        let ns_per_pixel = self.ns_per_pixel(bounds);
+        let x = NAME_WIDTH
+            + (event.start_ns.saturating_sub(self.timings.start_ns) as f64 / ns_per_pixel) as f32;
+        Rectangle {
+            x,
+            y: row as f32 * ROW_HEIGHT + 1.0,
+            width: ((event.duration_ns as f64 / ns_per_pixel) as f32).max(1.0),
+            height: ROW_HEIGHT - 2.0,
+        }
+    }
+
+    /// The innermost stage under `position`.
+    fn stage_at(&self, bounds: Rectangle, position: Point) -> Option<EventId> {
+        let row = (position.y / ROW_HEIGHT) as usize;
+        let cgu = self.timings.cgus.get(row)?;
+        cgu.stages.iter().rev().copied().find(|id| {
+            self.stage_rect(bounds, row, &self.events[id.index()])
+                .contains(position)
+        })
+    }
+}
+
+impl Program<Message> for GanttProgram<'_> {
+    type State = GanttState;
+
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<Action<Message>> {
+        match event {
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                let hovered = cursor
+                    .position_in(bounds)
+                    .and_then(|position| self.stage_at(bounds, position));
+                if hovered != state.hovered {
+                    state.hovered = hovered;
+                    return Some(Action::request_redraw());
+                }
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let id = cursor
+                    .position_in(bounds)
+                    .and_then(|position| self.stage_at(bounds, position))?;
+                let event = &self.events[id.index()];
+                let start_ns = event.start_ns.saturating_sub(self.min_ns);
+                return Some(
+                    Action::publish(Message::ShowRangeInTimeline {
+                        start_ns: start_ns as f64,
+                        end_ns: (start_ns + event.duration_ns) as f64,
+                        event: Some(id),
+                    })
+                    .and_capture(),
+                );
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn draw(
+        &self,
+        state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        let text_color = shade(theme, 0.8);
+
+        for (row, cgu) in self.timings.cgus.iter().enumerate() {
+            let y = row as f32 * ROW_HEIGHT;
+            if row % 2 == 1 {
+                frame.fill_rectangle(
+                    Point::new(0.0, y),
+                    Size::new(bounds.width, ROW_HEIGHT),
+                    Color {
+                        a: 0.03,
+                        ..shade(theme, 1.0)
+                    },
+                );
+            }
+            frame.with_clip(
+                Rectangle {
+                    x: 0.0,
+                    y,
+                    width: NAME_WIDTH - 4.0,
+                    height: ROW_HEIGHT,
+                },
+                |frame| {
+                    frame.fill_text(canvas::Text {
+                        content: self.symbols.resolve(cgu.name).to_string(),
+                        position: Point::new(4.0, y + 2.0),
+                        color: text_color,
+                        size: 11.0.into(),
+                        ..Default::default()
+                    });
+                },
+            );
+            for &id in &cgu.stages {
+                let event = &self.events[id.index()];
+                let rect = self.stage_rect(bounds, row, event);
+                let color = self
+                    .rule_colors
+                    .color(event)
+                    .unwrap_or_else(|| color_from_label(self.symbols.resolve(event.label)));
+                frame.fill_rectangle(rect.position(), rect.size(), color);
+                let border = if state.hovered == Some(id) {
+                    canvas::Stroke::default()
+                        .with_color(Color::from_rgb(0.0, 0.4, 0.8))
+                        .with_width(2.0)
+                } else {
+                    canvas::Stroke::default()
+                        .with_color(Color::from_rgba(0.0, 0.0, 0.0, 0.25))
+                        .with_width(1.0)
+                };
+                frame.stroke(
+                    &canvas::Path::rectangle(rect.position(), rect.size()),
+                    border,
+                );
+            }
+        }
+
+        // Describe the hovered stage next to its bar.
+        if let Some(id) = state.hovered
+            && let Some(row) = self
+                .timings
+                .cgus
+                .iter()
+                .position(|cgu| cgu.stages.contains(&id))
+        {
+            let event = &self.events[id.index()];
+            let rect = self.stage_rect(bounds, row, event);
+            let content = format!(
+                "{} · {} · {}",
+                self.symbols.resolve(event.label),
+                thread_name(self.thread_names, event.thread_id),
+                format_duration(event.duration_ns)
+            );
+            let width = content.chars().count() as f32 * 6.5 + 8.0;
+            let x = if rect.x + rect.width + width < bounds.width {
+                rect.x + rect.width + 4.0
+            } else {
+                (rect.x - width - 4.0).max(NAME_WIDTH)
+            };
+            frame.fill_rectangle(
+                Point::new(x, rect.y),
+                Size::new(width, rect.height),
+                Color {
+                    a: 0.9,
+                    ..shade(theme, 0.0)
+                },
+            );
+            frame.fill_text(canvas::Text {
+                content,
+                position: Point::new(x + 4.0, rect.y + 1.0),
+                color: text_color,
+                size: 11.0.into(),
+                ..Default::default()
+            });
+        }
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        state: &Self::State,
+        _bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        if state.hovered.is_some() {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
+
+pub fn view<'a>(
+    data: &'a FileData,
+    timings: &'a CguTimings,
+    rule_colors: &'a RuleColors,
+) -> Element<'a, Message> {
+    let content: Element<'a, Message> = if timings.cgus.is_empty() {
+        text("No codegen unit events in this profile.")
+            .size(12)
+            .into()
+    } else {
+        let mut by_total: Vec<&Cgu> = timings.cgus.iter().collect();
+        by_total.sort_by_key(|cgu| std::cmp::Reverse(cgu.total_ns));
+        let median_ns = by_total[by_total.len() / 2].total_ns;
+        let summary = text(format!(
+            "{} CGUs over {}, longest {}, median {} ({:.1}× median)",
+            timings.cgus.len(),
+            format_duration(timings.end_ns.saturating_sub(timings.start_ns)),
+            format_duration(by_total[0].total_ns),
+            format_duration(median_ns),
+            by_total[0].total_ns as f64 / median_ns.max(1) as f64
+        ))
+        .size(12);
+
+        let gantt = Canvas::new(GanttProgram {
+            timings,
+            events: &data.events,
+            symbols: &data.symbols,
+            rule_colors,
+            thread_names: &data.thread_names,
+            min_ns: data.timeline.min_ns,
+        })
+        .width(Length::Fill)
+        .height(Length::Fixed(timings.cgus.len() as f32 * ROW_HEIGHT));
+
+        let number = Length::Fixed(90.0);
+        let mut rows = column![
+            row![
+                text("CGU").size(12).width(Length::Fill),
+                text("Total").size(12).width(number),
+                text("Span").size(12).width(number),
+                text("Stages").size(12).width(number),
+                text("Threads").size(12).width(number),
+                text("").size(12).width(Length::Fixed(50.0)),
+            ]
+            .spacing(8)
+        ]
+        .spacing(2);
+        for cgu in by_total.iter().take(MAX_ROWS) {
+            let start_ns = cgu.start_ns.saturating_sub(data.timeline.min_ns);
+            let span_ns = cgu.end_ns.saturating_sub(cgu.start_ns);
+            rows = rows.push(
+                row![
+                    text(data.symbols.resolve(cgu.name))
+                        .size(12)
+                        .width(Length::Fill),
+                    text(format_duration(cgu.total_ns)).size(12).width(number),
+                    text(format_duration(span_ns)).size(12).width(number),
+                    text(format!("{}", cgu.stages.len())).size(12).width(number),
+                    text(format!("{}", cgu.threads)).size(12).width(number),
+                    crate::ui::show_range_button(
+                        start_ns as f64,
+                        (start_ns + span_ns) as f64,
+                        None,
+                    ),
+                ]
+                .spacing(8)
+                .align_y(iced::Alignment::Center),
+            );
+        }
+
+        column![
+            summary,
+            scrollable::Scrollable::new(gantt).height(Length::FillPortion(3)),
+            scrollable::Scrollable::new(rows).height(Length::FillPortion(2)),
+        ]
+        .spacing(8)
+        .into()
+    };
+
+    container(column![content].padding(10))
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(crate::ui::panel_style)
+        .into()
+}
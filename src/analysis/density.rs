@@ -0,0 +1,50 @@
+//! Event density over the whole profile, used for the mini timeline heatmap.
+
+use crate::data::TimelineEvent;
+
+/// Number of time bins the profile is divided into. This is finer than any
+/// realistic mini timeline width so bins can be merged per pixel.
+const DENSITY_BINS: usize = 4096;
+
+#[derive(Debug, Clone, Default)]
+pub struct ActivityDensity {
+    /// Number of events starting in each equally sized bin of the profile.
+    pub bins: Vec<u32>,
+    pub max_bin: u32,
+}
+
+impl ActivityDensity {
+    pub fn compute(events: &[TimelineEvent], min_ns: u64, max_ns: u64) -> Self {
+        let total_ns = max_ns.saturating_sub(min_ns).max(1) as f64;
+        let mut bins = vec![0u32; DENSITY_BINS];
+        for event in events.iter().filter(|event| !event.is_thread_root) {
+            let fraction = event.start_ns.saturating_sub(min_ns) as f64 / total_ns;
+            let bin = ((fraction * DENSITY_BINS as f64) as usize).min(DENSITY_BINS - 1);
+            bins[bin] = bins[bin].saturating_add(1);
+        }
+        let max_bin = bins.iter().copied().max().unwrap_or(0);
+        ActivityDensity { bins, max_bin }
+    }
+
+    /// Heat in `[0, 1]` for each of `columns` equal slices of the profile.
+    /// Uses the busiest bin of each slice on a log scale so sparse regions
+    /// stay visible next to very dense ones.
+    pub fn column_heat(&self, columns: usize) -> Vec<f32> {
+        if columns == 0 || self.max_bin == 0 {
+            return vec![0.0; columns];
+        }
+        let max = (self.max_bin as f32).ln_1p();
+        (0..columns)
+            .map(|column| {
+                let first = column * self.bins.len() / columns;
+                let last = ((column + 1) * self.bins.len() / columns).max(first + 1);
+                let count = self.bins[first..last.min(self.bins.len())]
+                    .iter()
+                    .copied()
+                    .max()
+                    .unwrap_or(0);
+                (count as f32).ln_1p() / max
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,45 @@
+//! Duration distribution of the instances of one label.
+
+use crate::data::{EventId, TimelineEvent, duration_bucket};
+
+/// Instance counts per power-of-two duration bucket, as used by the mipmaps.
+#[derive(Debug, Clone, Default)]
+pub struct DurationHistogram {
+    /// Bucket of `counts[0]`.
+    pub first_bucket: u32,
+    pub counts: Vec<u32>,
+    pub max_count: u32,
+}
+
+impl DurationHistogram {
+    pub fn compute(events: &[TimelineEvent], instances: &[EventId]) -> Self {
+        let buckets = || {
+            instances
+                .iter()
+                .map(|id| duration_bucket(events[id.index()].duration_ns))
+        };
+        let (Some(first_bucket), Some(last_bucket)) = (buckets().min(), buckets().max()) else {
+            return DurationHistogram::default();
+        };
+        let mut counts = vec![0u32; (last_bucket - first_bucket + 1) as usize];
+        for bucket in buckets() {
+            counts[(bucket - first_bucket) as usize] += 1;
+        }
+        let max_count = counts.iter().copied().max().unwrap_or(0);
+        DurationHistogram {
+            first_bucket,
+            counts,
+            max_count,
+        }
+    }
+
+    /// The `[start, end)` ns durations covered by `bucket`.
+    pub fn bucket_range_ns(bucket: u32) -> (u64, u64) {
+        let start = if bucket == 0 { 0 } else { 1u64 << bucket };
+        (start, 1u64.checked_shl(bucket + 1).unwrap_or(u64::MAX))
+    }
+
+    pub fn buckets(&self) -> std::ops::Range<u32> {
+        self.first_bucket..self.first_bucket + self.counts.len() as u32
+    }
+}
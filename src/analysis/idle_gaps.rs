@@ -0,0 +1,176 @@
+//! Idle time between consecutive top-level events of each thread.
+
+use crate::Message;
+use crate::data::{EventId, FileData};
+use crate::timeline::format_duration;
+use iced::widget::{Space, checkbox, column, container, pick_list, row, scrollable, text};
+use iced::{Alignment, Element, Length};
+
+/// Longest list shown; the total still covers every gap.
+const MAX_ROWS: usize = 500;
+
+/// Thresholds offered in the idle gap view.
+pub const THRESHOLDS_NS: [u64; 6] = [
+    10_000,
+    100_000,
+    1_000_000,
+    10_000_000,
+    100_000_000,
+    1_000_000_000,
+];
+
+/// A minimum gap length for the threshold pick list, displayed as a duration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GapThreshold(pub u64);
+
+impl std::fmt::Display for GapThreshold {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format_duration(self.0))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IdleGap {
+    pub thread_id: u32,
+    /// Absolute ns.
+    pub start_ns: u64,
+    pub end_ns: u64,
+    /// The top-level events on either side of the gap.
+    pub before: EventId,
+    pub after: EventId,
+}
+
+impl IdleGap {
+    pub fn duration_ns(&self) -> u64 {
+        self.end_ns.saturating_sub(self.start_ns)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct IdleGaps {
+    pub threshold_ns: u64,
+    /// Sorted by descending length.
+    pub gaps: Vec<IdleGap>,
+}
+
+impl IdleGaps {
+    pub fn compute(data: &FileData, threshold_ns: u64) -> Self {
+        let mut gaps = Vec::new();
+        for group in &data.timeline.thread_groups {
+            for thread in group.threads.iter() {
+                let mut previous: Option<(EventId, u64)> = None;
+                for &id in &thread.events {
+                    let event = &data.events[id.index()];
+                    if event.depth != 0 || event.is_thread_root {
+                        continue;
+                    }
+                    if let Some((before, end_ns)) = previous
+                        && event.start_ns.saturating_sub(end_ns) >= threshold_ns.max(1)
+                    {
+                        gaps.push(IdleGap {
+                            thread_id: thread.thread_id,
+                            start_ns: end_ns,
+                            end_ns: event.start_ns,
+                            before,
+                            after: id,
+                        });
+                    }
+                    previous = Some((id, event.start_ns.saturating_add(event.duration_ns)));
+                }
+            }
+        }
+        gaps.sort_by_key(|gap| std::cmp::Reverse(gap.duration_ns()));
+        IdleGaps { threshold_ns, gaps }
+    }
+}
+
+pub fn view<'a>(data: &'a FileData, gaps: &'a IdleGaps, shade: bool) -> Element<'a, Message> {
+    let min_ns = data.timeline.min_ns;
+    let total_idle_ns: u64 = gaps.gaps.iter().map(IdleGap::duration_ns).sum();
+    let label = |id: EventId| data.symbols.resolve(data.events[id.index()].label);
+
+    let controls = row![
+        text("Minimum gap:").size(12),
+        pick_list(
+            THRESHOLDS_NS.map(GapThreshold),
+            Some(GapThreshold(gaps.threshold_ns)),
+            |threshold| Message::IdleGapThresholdChanged(threshold.0),
+        )
+        .text_size(12)
+        .padding(3)
+        .style(crate::neutral_pick_list_style),
+        checkbox(shade)
+            .label("Shade gaps in the timeline")
+            .size(14)
+            .text_size(12)
+            .on_toggle(Message::ShadeIdleGapsToggled),
+        Space::new().width(Length::Fill),
+        text(format!(
+            "{} gaps, {} idle in total",
+            gaps.gaps.len(),
+            format_duration(total_idle_ns)
+        ))
+        .size(12),
+    ]
+    .spacing(10)
+    .align_y(Alignment::Center);
+
+    let header = row![
+        text("Length").size(12).width(Length::Fixed(80.0)),
+        text("Thread").size(12).width(Length::Fixed(80.0)),
+        text("Start").size(12).width(Length::Fixed(80.0)),
+        text("After").size(12).width(Length::FillPortion(1)),
+        text("Before").size(12).width(Length::FillPortion(1)),
+        Space::new().width(Length::Fixed(50.0)),
+    ]
+    .spacing(8);
+
+    let mut rows = column![].spacing(2);
+    for gap in gaps.gaps.iter().take(MAX_ROWS) {
+        // Show the gap with a little of the surrounding events for context.
+        let padding_ns = gap.duration_ns() / 10;
+        rows = rows.push(
+            row![
+                text(format_duration(gap.duration_ns()))
+                    .size(12)
+                    .width(Length::Fixed(80.0)),
+                text(data.thread_name(gap.thread_id))
+                    .size(12)
+                    .width(Length::Fixed(80.0)),
+                text(format_duration(gap.start_ns.saturating_sub(min_ns)))
+                    .size(12)
+                    .width(Length::Fixed(80.0)),
+                text(label(gap.before))
+                    .size(12)
+                    .width(Length::FillPortion(1)),
+                text(label(gap.after))
+                    .size(12)
+                    .width(Length::FillPortion(1)),
+                crate::ui::show_range_button(
+                    gap.start_ns.saturating_sub(min_ns + padding_ns) as f64,
+                    gap.end_ns.saturating_add(padding_ns).saturating_sub(min_ns) as f64,
+                    Some(gap.after),
+                ),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        );
+    }
+    if gaps.gaps.len() > MAX_ROWS {
+        rows = rows.push(text(format!("… {} more", gaps.gaps.len() - MAX_ROWS)).size(12));
+    }
+
+    container(
+        column![
+            controls,
+            header,
+            scrollable::Scrollable::new(rows).height(Length::Fill)
+        ]
+        .spacing(8)
+        .padding(10),
+    )
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .style(crate::ui::panel_style)
+    .into()
+}
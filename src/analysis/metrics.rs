@@ -0,0 +1,41 @@
+//! Per-event self time and parent duration, used by the derived color modes.
+
+use crate::data::FileData;
+
+#[derive(Debug, Clone, Default)]
+pub struct EventMetrics {
+    /// Indexed like `FileData::events`.
+    pub self_ns: Vec<u64>,
+    /// Duration of the enclosing event; 0 for top-level events.
+    pub parent_ns: Vec<u64>,
+}
+
+impl EventMetrics {
+    pub fn compute(data: &FileData) -> Self {
+        let events = &data.events;
+        let mut self_ns: Vec<u64> = events.iter().map(|event| event.duration_ns).collect();
+        let mut parent_ns = vec![0u64; events.len()];
+        for group in &data.timeline.thread_groups {
+            for thread in group.threads.iter() {
+                // Enclosing events of the current one; parents come before
+                // their children in the thread order.
+                let mut stack: Vec<usize> = Vec::new();
+                for id in &thread.events {
+                    let event = &events[id.index()];
+                    while stack
+                        .last()
+                        .is_some_and(|&parent| events[parent].depth >= event.depth)
+                    {
+                        stack.pop();
+                    }
+                    if let Some(&parent) = stack.last() {
+                        self_ns[parent] = self_ns[parent].saturating_sub(event.duration_ns);
+                        parent_ns[id.index()] = events[parent].duration_ns;
+                    }
+                    stack.push(id.index());
+                }
+            }
+        }
+        EventMetrics { self_ns, parent_ns }
+    }
+}
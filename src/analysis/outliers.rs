@@ -0,0 +1,276 @@
+//! Instances that run far longer than is usual for their label.
+
+use crate::Message;
+use crate::data::{EventId, FileData, TimelineEvent};
+use crate::symbols::{Symbol, Symbols};
+use crate::timeline::format_duration;
+use iced::widget::{Space, button, checkbox, column, container, pick_list, row, scrollable, text};
+use iced::{Alignment, Element, Length};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Labels with fewer instances have no meaningful distribution.
+const MIN_INSTANCES: usize = 20;
+
+/// Instances longer than this multiple of the median are outliers.
+const MEDIAN_FACTOR: u64 = 10;
+
+/// Longest list shown.
+const MAX_ROWS: usize = 500;
+
+/// Which instances count as outliers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OutlierRule {
+    #[default]
+    TimesMedian,
+    AboveP99,
+}
+
+impl OutlierRule {
+    pub const ALL: [OutlierRule; 2] = [OutlierRule::TimesMedian, OutlierRule::AboveP99];
+}
+
+impl std::fmt::Display for OutlierRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutlierRule::TimesMedian => write!(f, "Over {}× the median", MEDIAN_FACTOR),
+            OutlierRule::AboveP99 => write!(f, "Above the 99th percentile"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutlierSort {
+    #[default]
+    Duration,
+    Ratio,
+    Label,
+    Start,
+}
+
+impl OutlierSort {
+    /// Whether the list is sorted from the smallest value up.
+    fn ascending(self) -> bool {
+        matches!(self, OutlierSort::Label | OutlierSort::Start)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Outlier {
+    pub event: EventId,
+    pub median_ns: u64,
+    pub p99_ns: u64,
+    /// Number of instances of the label.
+    pub instances: usize,
+}
+
+impl Outlier {
+    pub fn ratio(&self, events: &[TimelineEvent]) -> f64 {
+        events[self.event.index()].duration_ns as f64 / self.median_ns.max(1) as f64
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Outliers {
+    pub rule: OutlierRule,
+    pub sort: OutlierSort,
+    pub outliers: Vec<Outlier>,
+}
+
+impl Outliers {
+    pub fn compute(data: &FileData, rule: OutlierRule, sort: OutlierSort) -> Self {
+        let events = &data.events;
+        let mut by_label: HashMap<Symbol, Vec<EventId>> = HashMap::new();
+        for (index, event) in events.iter().enumerate() {
+            if !event.is_thread_root {
+                by_label
+                    .entry(event.label)
+                    .or_default()
+                    .push(EventId(index as u32));
+            }
+        }
+
+        let outliers = by_label
+            .into_par_iter()
+            .filter(|(_, ids)| ids.len() >= MIN_INSTANCES)
+            .flat_map_iter(|(_, ids)| {
+                let mut durations: Vec<u64> = ids
+                    .iter()
+                    .map(|id| events[id.index()].duration_ns)
+                    .collect();
+                durations.sort_unstable();
+                let median_ns = durations[durations.len() / 2];
+                // Nearest-rank percentile.
+                let p99_ns = durations[(durations.len() * 99).div_ceil(100) - 1];
+                let limit_ns = match rule {
+                    OutlierRule::TimesMedian => median_ns.max(1).saturating_mul(MEDIAN_FACTOR),
+                    OutlierRule::AboveP99 => p99_ns,
+                };
+                let instances = ids.len();
+                ids.into_iter()
+                    .filter(move |id| events[id.index()].duration_ns > limit_ns)
+                    .map(move |event| Outlier {
+                        event,
+                        median_ns,
+                        p99_ns,
+                        instances,
+                    })
+            })
+            .collect();
+
+        let mut result = Outliers {
+            rule,
+            sort,
+            outliers,
+        };
+        result.sort_by(events, &data.symbols, sort);
+        result
+    }
+
+    /// Sort by `sort`: durations and ratios descending, labels and start
+    /// times ascending.
+    pub fn sort_by(&mut self, events: &[TimelineEvent], symbols: &Symbols, sort: OutlierSort) {
+        self.sort = sort;
+        let event = |outlier: &Outlier| &events[outlier.event.index()];
+        match sort {
+            OutlierSort::Duration => self
+                .outliers
+                .sort_by_key(|outlier| std::cmp::Reverse(event(outlier).duration_ns)),
+            OutlierSort::Ratio => self
+                .outliers
+                .sort_by(|a, b| b.ratio(events).total_cmp(&a.ratio(events))),
+            OutlierSort::Label => self.outliers.sort_by(|a, b| {
+                symbols
+                    .resolve(event(a).label)
+                    .cmp(symbols.resolve(event(b).label))
+                    .then(event(b).duration_ns.cmp(&event(a).duration_ns))
+            }),
+            OutlierSort::Start => self.outliers.sort_by_key(|outlier| event(outlier).start_ns),
+        }
+    }
+}
+
+fn sort_header<'a>(title: &str, sort: OutlierSort, current: OutlierSort) -> Element<'a, Message> {
+    let title = if sort == current {
+        format!("{} {}", title, if sort.ascending() { "▴" } else { "▾" })
+    } else {
+        title.to_string()
+    };
+    button(text(title).size(12))
+        .style(crate::ui::toolbar_button_style)
+        .padding(0)
+        .on_press(Message::OutlierSortChanged(sort))
+        .into()
+}
+
+pub fn view<'a>(data: &'a FileData, outliers: &'a Outliers, outline: bool) -> Element<'a, Message> {
+    let events = &data.events;
+    let min_ns = data.timeline.min_ns;
+
+    let controls = row![
+        text("Outliers:").size(12),
+        pick_list(
+            &OutlierRule::ALL[..],
+            Some(outliers.rule),
+            Message::OutlierRuleChanged,
+        )
+        .text_size(12)
+        .padding(3)
+        .style(crate::neutral_pick_list_style),
+        checkbox(outline)
+            .label("Outline in the timeline")
+            .size(14)
+            .text_size(12)
+            .on_toggle(Message::OutlineOutliersToggled),
+        Space::new().width(Length::Fill),
+        text(format!(
+            "{} instances of labels with at least {} instances",
+            outliers.outliers.len(),
+            MIN_INSTANCES
+        ))
+        .size(12),
+    ]
+    .spacing(10)
+    .align_y(Alignment::Center);
+
+    let header = row![
+        container(sort_header(
+            "Duration",
+            OutlierSort::Duration,
+            outliers.sort
+        ))
+        .width(Length::Fixed(80.0)),
+        container(sort_header("× median", OutlierSort::Ratio, outliers.sort))
+            .width(Length::Fixed(70.0)),
+        text("Median").size(12).width(Length::Fixed(80.0)),
+        text("p99").size(12).width(Length::Fixed(80.0)),
+        container(sort_header("Label", OutlierSort::Label, outliers.sort)).width(Length::Fill),
+        text("Thread").size(12).width(Length::Fixed(80.0)),
+        container(sort_header("Start", OutlierSort::Start, outliers.sort))
+            .width(Length::Fixed(80.0)),
+        Space::new().width(Length::Fixed(50.0)),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+
+    let mut rows = column![].spacing(2);
+    for outlier in outliers.outliers.iter().take(MAX_ROWS) {
+        let event = &events[outlier.event.index()];
+        let start_ns = event.start_ns.saturating_sub(min_ns);
+        let padding_ns = event.duration_ns / 10;
+        rows = rows.push(
+            row![
+                text(format_duration(event.duration_ns))
+                    .size(12)
+                    .width(Length::Fixed(80.0)),
+                text(format!("{:.1}×", outlier.ratio(events)))
+                    .size(12)
+                    .width(Length::Fixed(70.0)),
+                text(format_duration(outlier.median_ns))
+                    .size(12)
+                    .width(Length::Fixed(80.0)),
+                text(format_duration(outlier.p99_ns))
+                    .size(12)
+                    .width(Length::Fixed(80.0)),
+                text(format!(
+                    "{} ({} runs)",
+                    data.symbols.resolve(event.label),
+                    outlier.instances
+                ))
+                .size(12)
+                .width(Length::Fill),
+                text(data.thread_name(event.thread_id))
+                    .size(12)
+                    .width(Length::Fixed(80.0)),
+                text(format_duration(start_ns))
+                    .size(12)
+                    .width(Length::Fixed(80.0)),
+                crate::ui::show_range_button(
+                    start_ns.saturating_sub(padding_ns) as f64,
+                    (start_ns + event.duration_ns + padding_ns) as f64,
+                    Some(outlier.event),
+                ),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        );
+    }
+    if outliers.outliers.len() > MAX_ROWS {
+        rows = rows.push(text(format!("… {} more", outliers.outliers.len() - MAX_ROWS)).size(12));
+    }
+
+    container(
+        column![
+            controls,
+            header,
+            scrollable::Scrollable::new(rows).height(Length::Fill)
+        ]
+        .spacing(8)
+        .padding(10),
+    )
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .style(crate::ui::panel_style)
+    .into()
+}
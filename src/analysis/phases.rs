@@ -0,0 +1,212 @@
+//! High-level compiler phases derived from event labels.
+//!
+//! Each `PhaseRule` maps labels matching a regular expression to a phase; the
+//! first matching rule wins. Only the outermost matching event on each
+//! thread counts, and the intervals of a phase that are close together are
+//! merged into one band.
+
+use crate::data::{ThreadData, TimelineEvent};
+use crate::symbols::{Symbol, Symbols};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Gaps shorter than this fraction of the profile do not split a band.
+const MERGE_GAP_FRACTION: f64 = 0.01;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhaseRule {
+    pub phase: String,
+    /// Regular expression matched against event labels.
+    pub label: String,
+}
+
+impl PhaseRule {
+    fn new(phase: &str, label: &str) -> Self {
+        PhaseRule {
+            phase: phase.to_string(),
+            label: label.to_string(),
+        }
+    }
+
+    /// Rules for rustc's self-profile labels.
+    pub fn defaults() -> Vec<PhaseRule> {
+        vec![
+            PhaseRule::new(
+                "Parsing & expansion",
+                "^(parse_crate.*|expand_crate|macro_expand_crate|configure_and_expand|\
+                 resolver_for_lowering.*|hir_crate|lower_to_hir)$",
+            ),
+            PhaseRule::new(
+                "Analysis",
+                "^(analysis|type_check_crate|typeck|MIR_borrow_checking|lint_checking|\
+                 privacy_checking_modules|misc_checking_.*)$",
+            ),
+            PhaseRule::new("MIR", "^(optimized_mir|mir_.*|MIR_.*)$"),
+            PhaseRule::new(
+                "LLVM",
+                "^(LLVM_.*|codegen_module_optimize|codegen_module_perform_lto|\
+                 finish_ongoing_codegen)$",
+            ),
+            PhaseRule::new(
+                "Codegen",
+                "^(codegen_crate|codegen_module|monomorphization_collector.*|\
+                 collect_and_partition_mono_items|partition_and_assert_distinct_symbols)$",
+            ),
+            PhaseRule::new(
+                "Linking",
+                "^(link|link_crate|link_binary|link_rlib|run_linker)$",
+            ),
+        ]
+    }
+
+    /// Check the label expressions. Errors name each offending rule.
+    pub fn validate(rules: &[PhaseRule]) -> Result<(), String> {
+        let errors: Vec<String> = rules
+            .iter()
+            .enumerate()
+            .filter_map(|(index, rule)| {
+                let error = Regex::new(&rule.label).err()?;
+                Some(format!(
+                    "Phase rule {} ({}): invalid pattern: {}",
+                    index + 1,
+                    rule.phase,
+                    error
+                ))
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("\n"))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PhaseBand {
+    /// Index into `Phases::names`.
+    pub phase: usize,
+    /// Absolute ns.
+    pub start_ns: u64,
+    pub end_ns: u64,
+    /// Lane the band is drawn in, so overlapping bands do not cover each other.
+    pub lane: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Phases {
+    pub names: Vec<String>,
+    /// Ordered by start.
+    pub bands: Vec<PhaseBand>,
+    pub lanes: usize,
+}
+
+impl Phases {
+    /// Rules with invalid expressions are skipped; `PhaseRule::validate`
+    /// reports them.
+    pub fn compute(
+        events: &[TimelineEvent],
+        threads: &[Arc<ThreadData>],
+        symbols: &Symbols,
+        rules: &[PhaseRule],
+        total_ns: u64,
+    ) -> Self {
+        let mut names: Vec<String> = Vec::new();
+        let mut compiled: Vec<(usize, Regex)> = Vec::new();
+        for rule in rules {
+            let Ok(regex) = Regex::new(&rule.label) else {
+                continue;
+            };
+            let phase = match names.iter().position(|name| *name == rule.phase) {
+                Some(phase) => phase,
+                None => {
+                    names.push(rule.phase.clone());
+                    names.len() - 1
+                }
+            };
+            compiled.push((phase, regex));
+        }
+        if compiled.is_empty() {
+            return Phases::default();
+        }
+
+        let mut phase_of_label: HashMap<Symbol, Option<usize>> = HashMap::new();
+        let mut intervals: Vec<Vec<(u64, u64)>> = vec![Vec::new(); names.len()];
+        for thread in threads {
+            // End of the outermost matched event containing the current one.
+            let mut matched_end = 0u64;
+            for id in &thread.events {
+                let event = &events[id.index()];
+                if event.start_ns < matched_end {
+                    continue;
+                }
+                let phase = *phase_of_label.entry(event.label).or_insert_with(|| {
+                    let label = symbols.resolve(event.label);
+                    compiled
+                        .iter()
+                        .find(|(_, regex)| regex.is_match(label))
+                        .map(|(phase, _)| *phase)
+                });
+                if let Some(phase) = phase {
+                    let end_ns = event.start_ns + event.duration_ns;
+                    intervals[phase].push((event.start_ns, end_ns));
+                    matched_end = end_ns;
+                }
+            }
+        }
+
+        let merge_gap_ns = (total_ns as f64 * MERGE_GAP_FRACTION) as u64;
+        let mut bands = Vec::new();
+        for (phase, mut intervals) in intervals.into_iter().enumerate() {
+            intervals.sort_unstable();
+            let mut current: Option<(u64, u64)> = None;
+            for (start_ns, end_ns) in intervals {
+                current = match current {
+                    Some((band_start, band_end)) if start_ns <= band_end + merge_gap_ns => {
+                        Some((band_start, band_end.max(end_ns)))
+                    }
+                    Some((band_start, band_end)) => {
+                        bands.push(PhaseBand {
+                            phase,
+                            start_ns: band_start,
+                            end_ns: band_end,
+                            lane: 0,
+                        });
+                        Some((start_ns, end_ns))
+                    }
+                    None => Some((start_ns, end_ns)),
+                };
+            }
+            if let Some((start_ns, end_ns)) = current {
+                bands.push(PhaseBand {
+                    phase,
+                    start_ns,
+                    end_ns,
+                    lane: 0,
+                });
+            }
+        }
+
+        // Pack overlapping bands into lanes.
+        bands.sort_by_key(|band| band.start_ns);
+        let mut lane_ends: Vec<u64> = Vec::new();
+        for band in &mut bands {
+            band.lane = match lane_ends.iter().position(|&end| end <= band.start_ns) {
+                Some(lane) => lane,
+                None => {
+                    lane_ends.push(0);
+                    lane_ends.len() - 1
+                }
+            };
+            lane_ends[band.lane] = band.end_ns;
+        }
+
+        Phases {
+            names,
+            bands,
+            lanes: lane_ends.len(),
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! Table of query executions, cache hits and incremental loads per label.
+
+use crate::Message;
+use crate::data::{FileData, QueryStats};
+use crate::symbols::Symbols;
+use crate::timeline::format_duration;
+use iced::widget::{button, column, container, row, scrollable, text};
+use iced::{Alignment, Element, Length};
+
+/// Longest list shown.
+const MAX_ROWS: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuerySort {
+    Label,
+    Executions,
+    #[default]
+    ExecutionTime,
+    CacheHits,
+    HitRate,
+    IncrementalLoads,
+    IncrementalLoadTime,
+}
+
+impl QuerySort {
+    /// Whether the table is sorted from the smallest value up.
+    fn ascending(self) -> bool {
+        self == QuerySort::Label
+    }
+}
+
+fn sort_header<'a>(
+    title: &'static str,
+    sort: QuerySort,
+    current: QuerySort,
+    width: Length,
+) -> Element<'a, Message> {
+    let title = if sort == current {
+        format!("{} {}", title, if sort.ascending() { "▴" } else { "▾" })
+    } else {
+        title.to_string()
+    };
+    container(
+        button(text(title).size(12))
+            .style(crate::ui::toolbar_button_style)
+            .padding(0)
+            .on_press(Message::QuerySortChanged(sort)),
+    )
+    .width(width)
+    .into()
+}
+
+/// Order the table rows. The stats start out sorted by execution time.
+pub fn sort_queries(rows: &mut [QueryStats], symbols: &Symbols, sort: QuerySort) {
+    match sort {
+        // Numeric columns sort descending, the label ascending.
+        QuerySort::Label => rows.sort_by_key(|query| symbols.resolve(query.label)),
+        QuerySort::Executions => rows.sort_by_key(|query| std::cmp::Reverse(query.executions)),
+        QuerySort::ExecutionTime => rows.sort_by_key(|query| std::cmp::Reverse(query.execution_ns)),
+        QuerySort::CacheHits => rows.sort_by_key(|query| std::cmp::Reverse(query.cache_hits)),
+        QuerySort::HitRate => rows.sort_by(|a, b| {
+            let rate = |query: &QueryStats| query.hit_rate().unwrap_or(-1.0);
+            rate(b).total_cmp(&rate(a))
+        }),
+        QuerySort::IncrementalLoads => {
+            rows.sort_by_key(|query| std::cmp::Reverse(query.incremental_loads))
+        }
+        QuerySort::IncrementalLoadTime => {
+            rows.sort_by_key(|query| std::cmp::Reverse(query.incremental_load_ns))
+        }
+    }
+}
+
+pub fn view<'a>(data: &'a FileData, sort: QuerySort) -> Element<'a, Message> {
+    let stats = &data.query_stats;
+    let number = Length::Fixed(90.0);
+
+    let content: Element<'a, Message> = if stats.is_empty() {
+        text("No query events in this profile.").size(12).into()
+    } else {
+        let executions: u64 = stats.iter().map(|query| query.executions).sum();
+        let cache_hits: u64 = stats.iter().map(|query| query.cache_hits).sum();
+        let total = QueryStats {
+            executions,
+            cache_hits,
+            ..QueryStats::default()
+        };
+        let summary = text(format!(
+            "{} queries, {} executions, {} cache hits ({})",
+            stats.len(),
+            executions,
+            cache_hits,
+            format_rate(total.hit_rate())
+        ))
+        .size(12);
+
+        let header = row![
+            sort_header("Query", QuerySort::Label, sort, Length::Fill),
+            sort_header("Executions", QuerySort::Executions, sort, number),
+            sort_header("Time", QuerySort::ExecutionTime, sort, number),
+            sort_header("Cache hits", QuerySort::CacheHits, sort, number),
+            sort_header("Hit rate", QuerySort::HitRate, sort, number),
+            sort_header("Incr. loads", QuerySort::IncrementalLoads, sort, number),
+            sort_header("Load time", QuerySort::IncrementalLoadTime, sort, number),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center);
+
+        let mut rows = column![].spacing(2);
+        for query in stats.iter().take(MAX_ROWS) {
+            rows = rows.push(
+                row![
+                    text(data.symbols.resolve(query.label))
+                        .size(12)
+                        .width(Length::Fill),
+                    text(format!("{}", query.executions)).size(12).width(number),
+                    text(format_duration(query.execution_ns))
+                        .size(12)
+                        .width(number),
+                    text(format!("{}", query.cache_hits)).size(12).width(number),
+                    text(format_rate(query.hit_rate())).size(12).width(number),
+                    text(format!("{}", query.incremental_loads))
+                        .size(12)
+                        .width(number),
+                    text(format_duration(query.incremental_load_ns))
+                        .size(12)
+                        .width(number),
+                ]
+                .spacing(8),
+            );
+        }
+        if stats.len() > MAX_ROWS {
+            rows = rows.push(text(format!("… {} more", stats.len() - MAX_ROWS)).size(12));
+        }
+
+        column![
+            summary,
+            header,
+            scrollable::Scrollable::new(rows).height(Length::Fill)
+        ]
+        .spacing(8)
+        .into()
+    };
+
+    container(column![content].padding(10))
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(crate::ui::panel_style)
+        .into()
+}
+
+fn format_rate(rate: Option<f64>) -> String {
+    match rate {
+        Some(rate) => format!("{:.1}%", rate * 100.0),
+        None => "-".to_string(),
+    }
+}
@@ -0,0 +1,260 @@
+//! Time per query key (the `additional_data` recorded with `-Z
+//! self-profile-events=query-keys`), to find the items that are expensive to
+//! compile across all queries.
+
+use crate::Message;
+use crate::data::FileData;
+use crate::symbols::Symbol;
+use crate::timeline::format_duration;
+use iced::widget::{Space, checkbox, column, container, pick_list, row, scrollable, text};
+use iced::{Alignment, Element, Length};
+use std::collections::HashMap;
+
+/// Longest list shown.
+const MAX_ROWS: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyGrouping {
+    #[default]
+    Key,
+    LabelAndKey,
+}
+
+impl KeyGrouping {
+    pub const ALL: [KeyGrouping; 2] = [KeyGrouping::Key, KeyGrouping::LabelAndKey];
+}
+
+impl std::fmt::Display for KeyGrouping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyGrouping::Key => write!(f, "Key"),
+            KeyGrouping::LabelAndKey => write!(f, "Query and key"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyStats {
+    /// `None` when grouped by key alone.
+    pub label: Option<Symbol>,
+    pub key: String,
+    pub count: usize,
+    /// Time excluding nested events, so nested queries are not counted twice.
+    pub self_ns: u64,
+    pub total_ns: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct QueryKeys {
+    pub normalized: bool,
+    /// Both sorted by descending self time.
+    pub by_label_and_key: Vec<KeyStats>,
+    pub by_key: Vec<KeyStats>,
+}
+
+/// Reduce a query key to the item path it names: `DefId(0:12 ~ krate[1a2b]::foo)`
+/// becomes `krate::foo`, and generic arguments and crate disambiguators are
+/// dropped so all instances of an item share one key.
+pub fn normalize_key(key: &str) -> String {
+    let mut key = key.trim();
+    if let Some((_, path)) = key.split_once(" ~ ") {
+        key = path.strip_suffix(')').unwrap_or(path);
+    }
+
+    let mut normalized = String::with_capacity(key.len());
+    // Nesting depth of the generic arguments being skipped.
+    let mut skip_depth = 0usize;
+    let mut chars = key.char_indices();
+    while let Some((index, c)) = chars.next() {
+        if skip_depth > 0 {
+            match c {
+                '<' => skip_depth += 1,
+                '>' => skip_depth -= 1,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            // Generic arguments follow an identifier; any other `<` opens a
+            // qualified path like `<Foo as Bar>::baz`, which is kept.
+            '<' if normalized.ends_with(|c: char| c.is_alphanumeric() || c == '_') => {
+                skip_depth = 1;
+            }
+            // Skip disambiguators like `[1a2b]`.
+            '[' => {
+                let rest = &key[index + 1..];
+                match rest.find(']') {
+                    Some(end) if rest[..end].chars().all(|c| c.is_ascii_hexdigit()) => {
+                        for _ in 0..=end {
+                            chars.next();
+                        }
+                    }
+                    _ => normalized.push(c),
+                }
+            }
+            _ => normalized.push(c),
+        }
+    }
+    normalized
+}
+
+fn sorted(stats: HashMap<(Option<Symbol>, String), (usize, u64, u64)>) -> Vec<KeyStats> {
+    let mut sorted: Vec<KeyStats> = stats
+        .into_iter()
+        .map(|((label, key), (count, self_ns, total_ns))| KeyStats {
+            label,
+            key,
+            count,
+            self_ns,
+            total_ns,
+        })
+        .collect();
+    sorted.sort_by_key(|stats| std::cmp::Reverse(stats.self_ns));
+    sorted
+}
+
+impl QueryKeys {
+    pub fn compute(data: &FileData, normalized: bool) -> Self {
+        let events = &data.events;
+        let mut by_label_and_key: HashMap<(Option<Symbol>, String), (usize, u64, u64)> =
+            HashMap::new();
+        let mut by_key: HashMap<(Option<Symbol>, String), (usize, u64, u64)> = HashMap::new();
+        let mut key_strings: HashMap<&[Symbol], String> = HashMap::new();
+
+        for group in &data.timeline.thread_groups {
+            for thread in group.threads.iter() {
+                // Children follow their parent in the thread order, so the
+                // self time of an event is known once the next event at the
+                // same or a lower depth starts.
+                let mut stack: Vec<(usize, u64)> = Vec::new();
+                let mut finish = |(index, self_ns): (usize, u64)| {
+                    let event = &events[index];
+                    let Some(data_symbols) = event.additional_data.as_deref() else {
+                        return;
+                    };
+                    let key = key_strings
+                        .entry(data_symbols)
+                        .or_insert_with(|| {
+                            let key = data_symbols
+                                .iter()
+                                .map(|symbol| data.symbols.resolve(*symbol))
+                                .collect::<Vec<_>>()
+                                .join(", ");
+                            if normalized { normalize_key(&key) } else { key }
+                        })
+                        .clone();
+                    for (map, label) in [
+                        (&mut by_label_and_key, Some(event.label)),
+                        (&mut by_key, None),
+                    ] {
+                        let entry = map.entry((label, key.clone())).or_default();
+                        entry.0 += 1;
+                        entry.1 += self_ns;
+                        entry.2 += event.duration_ns;
+                    }
+                };
+                for id in &thread.events {
+                    let event = &events[id.index()];
+                    while let Some(&(parent, _)) = stack.last() {
+                        if events[parent].depth < event.depth {
+                            break;
+                        }
+                        finish(stack.pop().unwrap());
+                    }
+                    if let Some((_, parent_self_ns)) = stack.last_mut() {
+                        *parent_self_ns = parent_self_ns.saturating_sub(event.duration_ns);
+                    }
+                    stack.push((id.index(), event.duration_ns));
+                }
+                while let Some(entry) = stack.pop() {
+                    finish(entry);
+                }
+            }
+        }
+
+        QueryKeys {
+            normalized,
+            by_label_and_key: sorted(by_label_and_key),
+            by_key: sorted(by_key),
+        }
+    }
+}
+
+pub fn view<'a>(
+    data: &'a FileData,
+    keys: &'a QueryKeys,
+    grouping: KeyGrouping,
+) -> Element<'a, Message> {
+    let stats = match grouping {
+        KeyGrouping::Key => &keys.by_key,
+        KeyGrouping::LabelAndKey => &keys.by_label_and_key,
+    };
+
+    let controls = row![
+        text("Group by:").size(12),
+        pick_list(
+            &KeyGrouping::ALL[..],
+            Some(grouping),
+            Message::KeyGroupingChanged
+        )
+        .text_size(12)
+        .padding(3)
+        .style(crate::neutral_pick_list_style),
+        checkbox(keys.normalized)
+            .label("Normalize paths")
+            .size(14)
+            .text_size(12)
+            .on_toggle(Message::NormalizeQueryKeysToggled),
+        Space::new().width(Length::Fill),
+        text(format!("{} keys", stats.len())).size(12),
+    ]
+    .spacing(10)
+    .align_y(Alignment::Center);
+
+    let content: Element<'a, Message> = if stats.is_empty() {
+        text(
+            "No query keys in this profile. Record them with \
+             -Z self-profile-events=default,query-keys.",
+        )
+        .size(12)
+        .into()
+    } else {
+        let number = Length::Fixed(90.0);
+        let mut rows = column![
+            row![
+                text("Key").size(12).width(Length::FillPortion(3)),
+                text("Query").size(12).width(Length::FillPortion(1)),
+                text("Count").size(12).width(number),
+                text("Self time").size(12).width(number),
+                text("Total time").size(12).width(number),
+            ]
+            .spacing(8)
+        ]
+        .spacing(2);
+        for key in stats.iter().take(MAX_ROWS) {
+            let label = key.label.map_or("", |label| data.symbols.resolve(label));
+            rows = rows.push(
+                row![
+                    text(&key.key).size(12).width(Length::FillPortion(3)),
+                    text(label).size(12).width(Length::FillPortion(1)),
+                    text(format!("{}", key.count)).size(12).width(number),
+                    text(format_duration(key.self_ns)).size(12).width(number),
+                    text(format_duration(key.total_ns)).size(12).width(number),
+                ]
+                .spacing(8),
+            );
+        }
+        if stats.len() > MAX_ROWS {
+            rows = rows.push(text(format!("… {} more", stats.len() - MAX_ROWS)).size(12));
+        }
+        scrollable::Scrollable::new(rows)
+            .height(Length::Fill)
+            .into()
+    };
+
+    container(column![controls, content].spacing(8).padding(10))
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(crate::ui::panel_style)
+        .into()
+}
@@ -0,0 +1,217 @@
+//! Statistics for a measured time range of the timeline.
+
+use crate::Message;
+use crate::data::{FileData, KindInfo, thread_name};
+use crate::symbols::{Symbol, Symbols};
+use crate::timeline::format_duration;
+use iced::widget::{Space, button, column, container, row, scrollable, text};
+use iced::{Alignment, Element, Length};
+use std::collections::HashMap;
+
+/// Number of rows shown per table in the range panel.
+const MAX_ROWS: usize = 50;
+
+#[derive(Debug, Clone)]
+pub struct ThreadBusy {
+    pub thread_id: u32,
+    /// Time covered by top-level events of the thread inside the range.
+    pub busy_ns: u64,
+}
+
+/// Time spent inside `[start_ns, end_ns)`, with events clipped to the range.
+/// Label and kind times are self times, so nested events are not counted twice.
+#[derive(Debug, Clone)]
+pub struct RangeStats {
+    pub start_ns: u64,
+    pub end_ns: u64,
+    /// Sorted by descending time.
+    pub labels: Vec<(Symbol, u64)>,
+    /// Kind indices into `FileData::kinds`, sorted by descending time.
+    pub kinds: Vec<(u16, u64)>,
+    /// Sorted by thread id.
+    pub threads: Vec<ThreadBusy>,
+}
+
+impl RangeStats {
+    pub fn duration_ns(&self) -> u64 {
+        self.end_ns.saturating_sub(self.start_ns)
+    }
+}
+
+fn sorted_by_time<K>(times: HashMap<K, i64>) -> Vec<(K, u64)> {
+    let mut sorted: Vec<(K, u64)> = times
+        .into_iter()
+        .filter(|(_, time)| *time > 0)
+        .map(|(key, time)| (key, time as u64))
+        .collect();
+    sorted.sort_by_key(|(_, time)| std::cmp::Reverse(*time));
+    sorted
+}
+
+/// Compute the statistics for the absolute range `[start_ns, end_ns)`.
+pub fn compute(data: &FileData, start_ns: u64, end_ns: u64) -> RangeStats {
+    let mut label_times: HashMap<Symbol, i64> = HashMap::new();
+    let mut kind_times: HashMap<u16, i64> = HashMap::new();
+    let mut threads = Vec::new();
+
+    let clipped = |start: u64, duration: u64| -> u64 {
+        let end = start.saturating_add(duration).min(end_ns);
+        end.saturating_sub(start.max(start_ns))
+    };
+
+    for group in &data.timeline.thread_groups {
+        for thread in group.threads.iter() {
+            let mut busy_ns = 0u64;
+            // Enclosing events of the current event; thread events are ordered
+            // so parents come before their children.
+            let mut stack: Vec<&crate::data::TimelineEvent> = Vec::new();
+            for id in &thread.events {
+                let event = &data.events[id.index()];
+                if event.start_ns >= end_ns {
+                    break;
+                }
+                while stack
+                    .last()
+                    .is_some_and(|parent| parent.depth >= event.depth)
+                {
+                    stack.pop();
+                }
+                let time = clipped(event.start_ns, event.duration_ns);
+                if time > 0 {
+                    *label_times.entry(event.label).or_default() += time as i64;
+                    *kind_times.entry(event.kind_index).or_default() += time as i64;
+                    if let Some(parent) = stack.last() {
+                        *label_times.entry(parent.label).or_default() -= time as i64;
+                        *kind_times.entry(parent.kind_index).or_default() -= time as i64;
+                    }
+                    if event.depth == 0 {
+                        busy_ns += time;
+                    }
+                }
+                stack.push(event);
+            }
+            threads.push(ThreadBusy {
+                thread_id: thread.thread_id,
+                busy_ns,
+            });
+        }
+    }
+    threads.sort_by_key(|thread| thread.thread_id);
+
+    RangeStats {
+        start_ns,
+        end_ns,
+        labels: sorted_by_time(label_times),
+        kinds: sorted_by_time(kind_times),
+        threads,
+    }
+}
+
+fn percent(part: u64, total: u64) -> String {
+    format!("{:.1}%", part as f64 * 100.0 / total.max(1) as f64)
+}
+
+fn table<'a>(
+    title: &'a str,
+    rows: impl Iterator<Item = (String, u64)>,
+    total_ns: u64,
+) -> Element<'a, Message> {
+    let mut col = column![text(title).size(12)].spacing(2);
+    for (name, time) in rows.take(MAX_ROWS) {
+        col = col.push(
+            row![
+                text(format_duration(time))
+                    .size(11)
+                    .width(Length::Fixed(70.0)),
+                text(percent(time, total_ns))
+                    .size(11)
+                    .width(Length::Fixed(50.0)),
+                text(name).size(11),
+            ]
+            .spacing(6),
+        );
+    }
+    scrollable::Scrollable::new(col)
+        .width(Length::FillPortion(1))
+        .height(Length::Fill)
+        .into()
+}
+
+/// Panel listing where the time inside the measured range went.
+pub fn view<'a>(
+    stats: &'a RangeStats,
+    min_ns: u64,
+    symbols: &'a Symbols,
+    kinds: &'a [KindInfo],
+    thread_names: &HashMap<u32, String>,
+) -> Element<'a, Message> {
+    let duration_ns = stats.duration_ns();
+    // Self times are shares of the combined thread time, not of the range
+    // length, since several threads run concurrently.
+    let thread_time: u64 = stats.labels.iter().map(|(_, time)| time).sum();
+
+    let labels = table(
+        "By label (self time)",
+        stats
+            .labels
+            .iter()
+            .map(|(label, time)| (symbols.resolve(*label).to_string(), *time)),
+        thread_time,
+    );
+    let kinds = table(
+        "By kind (self time)",
+        stats.kinds.iter().map(|(kind, time)| {
+            let name = kinds
+                .get(*kind as usize)
+                .map(|info| symbols.resolve(info.kind).to_string())
+                .unwrap_or_default();
+            (name, *time)
+        }),
+        thread_time,
+    );
+    let threads = table(
+        "Thread busy time",
+        stats
+            .threads
+            .iter()
+            .map(|thread| (thread_name(thread_names, thread.thread_id), thread.busy_ns)),
+        duration_ns,
+    );
+
+    let header = row![
+        text("Range").size(14),
+        text(format!(
+            "{} – {} ({})",
+            format_duration(stats.start_ns.saturating_sub(min_ns)),
+            format_duration(stats.end_ns.saturating_sub(min_ns)),
+            format_duration(duration_ns)
+        ))
+        .size(12),
+        Space::new().width(Length::Fill),
+        button(text("Zoom to range").size(12))
+            .style(crate::ui::toolbar_button_style)
+            .padding(3)
+            .on_press(Message::TimelineZoomTo {
+                start_ns: stats.start_ns.saturating_sub(min_ns) as f64,
+                end_ns: stats.end_ns.saturating_sub(min_ns) as f64,
+            }),
+        button(text("Clear").size(12))
+            .style(crate::ui::toolbar_button_style)
+            .padding(3)
+            .on_press(Message::RangeCleared),
+    ]
+    .spacing(10)
+    .padding(5)
+    .align_y(Alignment::Center);
+
+    container(column![
+        header,
+        row![labels, kinds, threads]
+            .spacing(12)
+            .padding([0, 10])
+            .height(Length::Fixed(160.0)),
+    ])
+    .width(Length::Fill)
+    .style(crate::ui::panel_style)
+    .into()
+}
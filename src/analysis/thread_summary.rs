@@ -0,0 +1,88 @@
+//! Per-thread totals and activity shown next to the thread labels.
+
+use crate::data::{ThreadData, ThreadGroup, TimelineEvent};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Number of columns in the activity sparkline.
+pub const SPARKLINE_BUCKETS: usize = 32;
+
+#[derive(Debug, Clone)]
+pub struct ThreadSummary {
+    pub event_count: usize,
+    /// Total duration of the top-level events.
+    pub busy_ns: u64,
+    /// Busy fraction of each of `SPARKLINE_BUCKETS` equal slices of the
+    /// profile.
+    pub activity: [f32; SPARKLINE_BUCKETS],
+}
+
+impl ThreadSummary {
+    fn compute(events: &[TimelineEvent], thread: &ThreadData, max_ns: u64) -> Self {
+        let bucket_ns = (max_ns as f64 / SPARKLINE_BUCKETS as f64).max(1.0);
+        let mut busy_ns = 0u64;
+        let mut activity = [0.0f32; SPARKLINE_BUCKETS];
+        for id in &thread.events {
+            let event = &events[id.index()];
+            if event.depth != 0 {
+                continue;
+            }
+            busy_ns += event.duration_ns;
+            let start = event.start_ns as f64;
+            let end = start + event.duration_ns as f64;
+            let first = ((start / bucket_ns) as usize).min(SPARKLINE_BUCKETS - 1);
+            let last = ((end / bucket_ns) as usize).min(SPARKLINE_BUCKETS - 1);
+            for (bucket, value) in activity.iter_mut().enumerate().take(last + 1).skip(first) {
+                let bucket_start = bucket as f64 * bucket_ns;
+                let overlap = end.min(bucket_start + bucket_ns) - start.max(bucket_start);
+                *value += (overlap.max(0.0) / bucket_ns) as f32;
+            }
+        }
+        ThreadSummary {
+            event_count: thread.events.len(),
+            busy_ns,
+            activity,
+        }
+    }
+
+    /// Summaries of every thread, keyed by thread id.
+    pub fn compute_all(
+        events: &[TimelineEvent],
+        threads: &[Arc<ThreadData>],
+        max_ns: u64,
+    ) -> HashMap<u32, ThreadSummary> {
+        threads
+            .iter()
+            .map(|thread| (thread.thread_id, Self::compute(events, thread, max_ns)))
+            .collect()
+    }
+
+    /// Combined summary of a group: counts and busy time add up, activity is
+    /// averaged over the threads.
+    pub fn of_group(summaries: &HashMap<u32, ThreadSummary>, group: &ThreadGroup) -> Self {
+        let mut total = ThreadSummary {
+            event_count: 0,
+            busy_ns: 0,
+            activity: [0.0; SPARKLINE_BUCKETS],
+        };
+        let mut threads = 0;
+        for summary in group
+            .threads
+            .iter()
+            .filter_map(|thread| summaries.get(&thread.thread_id))
+        {
+            total.event_count += summary.event_count;
+            total.busy_ns += summary.busy_ns;
+            for (sum, value) in total.activity.iter_mut().zip(summary.activity) {
+                *sum += value;
+            }
+            threads += 1;
+        }
+        if threads > 1 {
+            for value in &mut total.activity {
+                *value /= threads as f32;
+            }
+        }
+        total
+    }
+}
@@ -0,0 +1,101 @@
+//! Number of busy threads over time, derived from the top-level events of
+//! each thread.
+
+use crate::data::{ThreadData, TimelineEvent};
+use std::sync::Arc;
+
+/// A step function of the number of threads running a top-level event.
+#[derive(Debug, Clone, Default)]
+pub struct Utilization {
+    /// `(time, busy)` pairs: `busy` threads are running from `time` until the
+    /// next step. Times are absolute ns and strictly increasing.
+    pub steps: Vec<(u64, u32)>,
+    pub max_busy: u32,
+}
+
+impl Utilization {
+    pub fn compute(events: &[TimelineEvent], threads: &[Arc<ThreadData>]) -> Self {
+        let mut changes: Vec<(u64, i32)> = Vec::new();
+        for thread in threads {
+            for id in &thread.events {
+                let event = &events[id.index()];
+                if event.depth == 0 && event.duration_ns > 0 {
+                    changes.push((event.start_ns, 1));
+                    changes.push((event.start_ns.saturating_add(event.duration_ns), -1));
+                }
+            }
+        }
+        changes.sort_unstable_by_key(|&(time, _)| time);
+
+        let mut steps: Vec<(u64, u32)> = Vec::new();
+        let mut busy = 0i32;
+        let mut max_busy = 0u32;
+        for (time, delta) in changes {
+            busy += delta;
+            let value = busy.max(0) as u32;
+            max_busy = max_busy.max(value);
+            match steps.last_mut() {
+                Some(last) if last.0 == time => last.1 = value,
+                _ => steps.push((time, value)),
+            }
+        }
+        steps.dedup_by(|next, prev| next.1 == prev.1);
+
+        Utilization { steps, max_busy }
+    }
+
+    /// Average number of busy threads in each of `columns` consecutive
+    /// windows of `ns_per_column` starting at the absolute time `start_ns`.
+    pub fn column_averages(&self, start_ns: f64, ns_per_column: f64, columns: usize) -> Vec<f32> {
+        let mut averages = vec![0.0f32; columns];
+        if self.steps.is_empty() || columns == 0 || ns_per_column <= 0.0 {
+            return averages;
+        }
+        let end_ns = start_ns + ns_per_column * columns as f64;
+        let first = self
+            .steps
+            .partition_point(|&(time, _)| (time as f64) <= start_ns)
+            .saturating_sub(1);
+
+        for (index, &(time, busy)) in self.steps.iter().enumerate().skip(first) {
+            let segment_start = (time as f64).max(start_ns);
+            if segment_start >= end_ns {
+                break;
+            }
+            let segment_end = self
+                .steps
+                .get(index + 1)
+                .map_or(end_ns, |&(next, _)| next as f64)
+                .min(end_ns);
+            if busy == 0 || segment_end <= segment_start {
+                continue;
+            }
+
+            let first_column = ((segment_start - start_ns) / ns_per_column) as usize;
+            let last_column =
+                (((segment_end - start_ns) / ns_per_column) as usize).min(columns - 1);
+            for (column, average) in averages
+                .iter_mut()
+                .enumerate()
+                .take(last_column + 1)
+                .skip(first_column)
+            {
+                let column_start = start_ns + column as f64 * ns_per_column;
+                let column_end = column_start + ns_per_column;
+                let overlap = segment_end.min(column_end) - segment_start.max(column_start);
+                if overlap > 0.0 {
+                    *average += (overlap * busy as f64 / ns_per_column) as f32;
+                }
+            }
+        }
+        averages
+    }
+
+    /// Average number of busy threads over the absolute range `[start_ns, end_ns)`.
+    pub fn average(&self, start_ns: f64, end_ns: f64) -> f64 {
+        if end_ns <= start_ns {
+            return 0.0;
+        }
+        self.column_averages(start_ns, end_ns - start_ns, 1)[0] as f64
+    }
+}
@@ -0,0 +1,213 @@
+//! User color rules, applied on top of any `ColorMode`.
+//!
+//! The rules live in `lineme/color_rules.txt` next to the settings file, one
+//! rule per line:
+//!
+//! ```text
+//! # Comments start with '#'.
+//! label ~ "^LLVM" => orange
+//! kind ~ "Query" => #4a90d9
+//! ```
+//!
+//! The first matching rule wins. Rules are resolved once per label and kind
+//! when a file is loaded or the rules change, so drawing only does a lookup.
+
+use crate::data::{KindInfo, TimelineEvent};
+use crate::symbols::{Symbol, Symbols};
+use iced::Color;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const COLOR_RULES_FILE_NAME: &str = "color_rules.txt";
+
+const NAMED_COLORS: [(&str, Color); 13] = [
+    ("red", Color::from_rgb(0.91, 0.36, 0.33)),
+    ("orange", Color::from_rgb(0.98, 0.62, 0.27)),
+    ("yellow", Color::from_rgb(0.98, 0.86, 0.36)),
+    ("green", Color::from_rgb(0.45, 0.78, 0.42)),
+    ("teal", Color::from_rgb(0.36, 0.75, 0.73)),
+    ("blue", Color::from_rgb(0.40, 0.61, 0.89)),
+    ("purple", Color::from_rgb(0.66, 0.50, 0.86)),
+    ("pink", Color::from_rgb(0.95, 0.58, 0.76)),
+    ("brown", Color::from_rgb(0.66, 0.51, 0.38)),
+    ("grey", Color::from_rgb(0.70, 0.70, 0.70)),
+    ("gray", Color::from_rgb(0.70, 0.70, 0.70)),
+    ("black", Color::from_rgb(0.15, 0.15, 0.15)),
+    ("white", Color::from_rgb(0.98, 0.98, 0.98)),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleField {
+    Label,
+    Kind,
+}
+
+#[derive(Debug, Clone)]
+pub struct ColorRule {
+    pub field: RuleField,
+    pub pattern: Regex,
+    pub color: Color,
+}
+
+/// The parsed rules together with the text they came from, so the settings
+/// editor can show the file as the user wrote it.
+#[derive(Debug, Clone, Default)]
+pub struct ColorRules {
+    pub source: String,
+    pub rules: Vec<ColorRule>,
+}
+
+fn parse_color(value: &str) -> Result<Color, String> {
+    if let Some(hex) = value.strip_prefix('#') {
+        let channel = |range: std::ops::Range<usize>| {
+            hex.get(range)
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+        };
+        return match (hex.len(), channel(0..2), channel(2..4), channel(4..6)) {
+            (6, Some(r), Some(g), Some(b)) => Ok(Color::from_rgb8(r, g, b)),
+            _ => Err(format!("invalid color '{}', expected #rrggbb", value)),
+        };
+    }
+    NAMED_COLORS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(value))
+        .map(|&(_, color)| color)
+        .ok_or_else(|| format!("unknown color '{}'", value))
+}
+
+fn parse_rule(line: &str) -> Result<ColorRule, String> {
+    let (matcher, color) = line
+        .rsplit_once("=>")
+        .ok_or_else(|| "expected '=> <color>'".to_string())?;
+    let (field, pattern) = matcher
+        .split_once('~')
+        .ok_or_else(|| "expected '<label|kind> ~ \"<regex>\"'".to_string())?;
+    let field = match field.trim() {
+        "label" => RuleField::Label,
+        "kind" => RuleField::Kind,
+        other => return Err(format!("unknown field '{}', expected label or kind", other)),
+    };
+    let pattern = pattern
+        .trim()
+        .strip_prefix('"')
+        .and_then(|pattern| pattern.strip_suffix('"'))
+        .ok_or_else(|| "the pattern must be quoted".to_string())?;
+    let pattern = Regex::new(pattern).map_err(|e| format!("invalid pattern: {}", e))?;
+    Ok(ColorRule {
+        field,
+        pattern,
+        color: parse_color(color.trim())?,
+    })
+}
+
+/// Path of the color rules file, if the platform has a config directory.
+pub fn color_rules_path() -> Option<PathBuf> {
+    crate::config::config_dir().map(|dir| dir.join(COLOR_RULES_FILE_NAME))
+}
+
+impl ColorRules {
+    /// Parse rules text. Errors name the offending line.
+    pub fn parse(source: &str) -> Result<ColorRules, String> {
+        let mut rules = Vec::new();
+        for (index, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            rules.push(parse_rule(line).map_err(|e| format!("Line {}: {}", index + 1, e))?);
+        }
+        Ok(ColorRules {
+            source: source.to_string(),
+            rules,
+        })
+    }
+
+    /// Read the text of the rules file without parsing it, so text that
+    /// fails to parse can still be edited. A missing file yields no text.
+    pub fn load_source() -> Result<String, String> {
+        let Some(path) = color_rules_path() else {
+            return Ok(String::new());
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => Ok(contents),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+            Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+        }
+    }
+
+    /// Write the rules file, creating the config directory if needed.
+    pub fn save(&self) -> Result<(), String> {
+        let path = color_rules_path().ok_or_else(|| "No config directory available".to_string())?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        std::fs::write(&path, &self.source)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    // Index and color of the first rule for `field` matching `value`.
+    fn first_match(&self, field: RuleField, value: &str) -> Option<(usize, Color)> {
+        self.rules
+            .iter()
+            .enumerate()
+            .find(|(_, rule)| rule.field == field && rule.pattern.is_match(value))
+            .map(|(index, rule)| (index, rule.color))
+    }
+
+    /// Resolve the rules against the labels and kinds of one file.
+    pub fn resolve(
+        &self,
+        events: &[TimelineEvent],
+        kinds: &[KindInfo],
+        symbols: &Symbols,
+    ) -> RuleColors {
+        if self.rules.is_empty() {
+            return RuleColors::default();
+        }
+        let mut by_label = HashMap::new();
+        for event in events {
+            by_label.entry(event.label).or_insert_with(|| {
+                self.first_match(RuleField::Label, symbols.resolve(event.label))
+            });
+        }
+        let by_kind = kinds
+            .iter()
+            .map(|kind| self.first_match(RuleField::Kind, symbols.resolve(kind.kind)))
+            .collect();
+        RuleColors {
+            by_label: by_label
+                .into_iter()
+                .filter_map(|(label, color)| Some((label, color?)))
+                .collect(),
+            by_kind,
+        }
+    }
+}
+
+/// Rule colors of one file, looked up by every view that colors events.
+#[derive(Debug, Clone, Default)]
+pub struct RuleColors {
+    /// Matching rule index and color per label; labels without a match are
+    /// left out.
+    by_label: HashMap<Symbol, (usize, Color)>,
+    /// Indexed like `FileData::kinds`.
+    by_kind: Vec<Option<(usize, Color)>>,
+}
+
+impl RuleColors {
+    /// The color the rules assign to `event`, if any.
+    pub fn color(&self, event: &TimelineEvent) -> Option<Color> {
+        let label = self.by_label.get(&event.label).copied();
+        let kind = self
+            .by_kind
+            .get(event.kind_index as usize)
+            .copied()
+            .flatten();
+        match (label, kind) {
+            (Some(label), Some(kind)) => Some(if label.0 <= kind.0 { label.1 } else { kind.1 }),
+            (label, kind) => label.or(kind).map(|(_, color)| color),
+        }
+    }
+}
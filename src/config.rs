@@ -0,0 +1,156 @@
+//! Persistent user preferences.
+//!
+//! The settings live in `lineme/settings.toml` under the platform config
+//! directory (`$XDG_CONFIG_HOME` or `~/.config` on Linux, `%APPDATA%` on
+//! Windows). Missing fields fall back to their defaults so older files keep
+//! loading after new options are added.
+
+use crate::ViewType;
+use crate::analysis::outliers::OutlierRule;
+use crate::analysis::phases::PhaseRule;
+use crate::data::{ColorMode, KindPalette, MergeStrategy};
+use crate::thread_names::ThreadNameRule;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const SETTINGS_FILE_NAME: &str = "settings.toml";
+
+/// The application theme. The palette itself is built in `Lineme::theme`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AppTheme {
+    #[default]
+    Light,
+    Dark,
+}
+
+impl AppTheme {
+    pub const ALL: [AppTheme; 2] = [AppTheme::Light, AppTheme::Dark];
+}
+
+impl std::fmt::Display for AppTheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppTheme::Light => write!(f, "Light"),
+            AppTheme::Dark => write!(f, "Dark"),
+        }
+    }
+}
+
+/// User preferences applied to newly opened files and to the application
+/// as a whole.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub color_mode: ColorMode,
+    pub merge_strategy: MergeStrategy,
+    pub default_view: ViewType,
+    pub theme: AppTheme,
+    pub kind_palette: KindPalette,
+    /// Start with every thread group collapsed to its top lane.
+    pub collapse_threads: bool,
+    /// Reopen the tabs of the previous run when starting without a file.
+    pub restore_session: bool,
+    /// Draw the busy thread curve inside the mini timeline.
+    pub mini_timeline_utilization: bool,
+    /// Shortest idle time between top-level events listed as a gap.
+    pub idle_gap_threshold_ns: u64,
+    pub outlier_rule: OutlierRule,
+    /// Label to phase mapping for the phase track, applied when a file is
+    /// loaded. The first rule whose `label` expression matches wins.
+    pub phase_rules: Vec<PhaseRule>,
+    /// Names for threads of every profile, e.g. the thread that starts
+    /// first. The first rule selecting a thread names it.
+    pub thread_name_rules: Vec<ThreadNameRule>,
+    /// Replaced by `merge_strategy`; only read from older files.
+    #[serde(skip_serializing)]
+    merge_threads: Option<bool>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            color_mode: ColorMode::default(),
+            merge_strategy: MergeStrategy::default(),
+            default_view: ViewType::default(),
+            theme: AppTheme::default(),
+            kind_palette: KindPalette::default(),
+            collapse_threads: false,
+            restore_session: true,
+            mini_timeline_utilization: false,
+            idle_gap_threshold_ns: 1_000_000,
+            outlier_rule: OutlierRule::default(),
+            phase_rules: PhaseRule::defaults(),
+            thread_name_rules: ThreadNameRule::defaults(),
+            merge_threads: None,
+        }
+    }
+}
+
+/// Directory holding all LineMe configuration files, if the platform has one.
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("lineme"))
+}
+
+/// Path of the settings file, if the platform has a config directory.
+pub fn settings_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(SETTINGS_FILE_NAME))
+}
+
+/// Report a file that failed to parse, moving it to `<name>.bak` first so
+/// the defaults saved in its place do not destroy the user's edits.
+pub fn parse_error(path: &Path, error: impl std::fmt::Display) -> String {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    let backup = PathBuf::from(backup);
+    match std::fs::rename(path, &backup) {
+        Ok(()) => format!(
+            "Failed to parse {}: {}; moved it to {}",
+            path.display(),
+            error,
+            backup.display()
+        ),
+        Err(e) => format!(
+            "Failed to parse {}: {}; could not move it aside: {}",
+            path.display(),
+            error,
+            e
+        ),
+    }
+}
+
+impl Config {
+    /// Load the settings file. A missing file yields the defaults; a file
+    /// that cannot be read or parsed is reported as an error, and one that
+    /// cannot be parsed is moved aside.
+    pub fn load() -> Result<Config, String> {
+        let Some(path) = settings_path() else {
+            return Ok(Config::default());
+        };
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                return Err(parse_error(&path, e));
+            }
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        let mut config: Config = toml::from_str(&contents).map_err(|e| parse_error(&path, e))?;
+        if let Some(merge_threads) = config.merge_threads.take() {
+            config.merge_strategy = MergeStrategy::from_merge_threads(merge_threads);
+        }
+        Ok(config)
+    }
+
+    /// Write the settings file, creating the config directory if needed.
+    pub fn save(&self) -> Result<(), String> {
+        let path = settings_path().ok_or_else(|| "No config directory available".to_string())?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let contents = toml::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        std::fs::write(&path, contents)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
@@ -26,6 +26,7 @@ use analyzeme::ProfilingData;
 use iced::Color;
 use intervaltree::IntervalTree;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
@@ -33,15 +34,37 @@ use std::sync::Arc;
 // ColorMode, color helper and display_depth are part of the shared public
 // API used by UI code. Define them here so data logic doesn't depend on
 // timeline UI internals.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum ColorMode {
     #[default]
     Kind,
     Event,
+    /// Heat scale of the duration relative to the visible range.
+    Duration,
+    /// Heat scale of the self time relative to the visible range.
+    SelfTime,
+    Thread,
+    /// The first `additional_data` value, e.g. the crate or CGU.
+    FirstData,
+    /// Heat scale of the share of the parent's duration.
+    PercentOfParent,
 }
 
 impl ColorMode {
-    pub const ALL: [ColorMode; 2] = [ColorMode::Kind, ColorMode::Event];
+    pub const ALL: [ColorMode; 7] = [
+        ColorMode::Kind,
+        ColorMode::Event,
+        ColorMode::Duration,
+        ColorMode::SelfTime,
+        ColorMode::Thread,
+        ColorMode::FirstData,
+        ColorMode::PercentOfParent,
+    ];
+
+    /// Whether the mode needs `FileUi::event_metrics`.
+    pub fn needs_metrics(self) -> bool {
+        matches!(self, ColorMode::SelfTime | ColorMode::PercentOfParent)
+    }
 }
 
 impl std::fmt::Display for ColorMode {
@@ -49,6 +72,80 @@ impl std::fmt::Display for ColorMode {
         match self {
             ColorMode::Kind => write!(f, "Kind"),
             ColorMode::Event => write!(f, "Event"),
+            ColorMode::Duration => write!(f, "Duration"),
+            ColorMode::SelfTime => write!(f, "Self time"),
+            ColorMode::Thread => write!(f, "Thread"),
+            ColorMode::FirstData => write!(f, "First data value"),
+            ColorMode::PercentOfParent => write!(f, "% of parent"),
+        }
+    }
+}
+
+/// Colors assigned to event kinds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum KindPalette {
+    /// Evenly spaced light hues.
+    #[default]
+    Pastel,
+    /// The Okabe-Ito palette, distinguishable with common color vision
+    /// deficiencies.
+    ColorblindSafe,
+    /// Saturated, dark hues with light label text.
+    HighContrast,
+}
+
+const OKABE_ITO: [Color; 7] = [
+    Color::from_rgb(0.902, 0.624, 0.0),
+    Color::from_rgb(0.337, 0.706, 0.914),
+    Color::from_rgb(0.0, 0.620, 0.451),
+    Color::from_rgb(0.941, 0.894, 0.259),
+    Color::from_rgb(0.0, 0.447, 0.698),
+    Color::from_rgb(0.835, 0.369, 0.0),
+    Color::from_rgb(0.800, 0.475, 0.655),
+];
+
+impl KindPalette {
+    pub const ALL: [KindPalette; 3] = [
+        KindPalette::Pastel,
+        KindPalette::ColorblindSafe,
+        KindPalette::HighContrast,
+    ];
+
+    /// Color of the `index`th of `count` kinds.
+    pub fn color(self, index: usize, count: usize) -> Color {
+        match self {
+            KindPalette::Pastel => {
+                let step = 360.0 / count.max(1) as f32;
+                color_from_hsl((120.0 + index as f32 * step) % 360.0, 0.35, 0.8)
+            }
+            KindPalette::ColorblindSafe => {
+                // Past the seven base colors, repeat them lighter rather than
+                // introducing hues that are hard to tell apart.
+                let base = OKABE_ITO[index % OKABE_ITO.len()];
+                let tint = ((index / OKABE_ITO.len()) as f32 * 0.35).min(0.7);
+                Color::from_rgb(
+                    base.r + (1.0 - base.r) * tint,
+                    base.g + (1.0 - base.g) * tint,
+                    base.b + (1.0 - base.b) * tint,
+                )
+            }
+            KindPalette::HighContrast => {
+                // Alternate the lightness so neighbouring hues also differ in
+                // brightness.
+                let step = 360.0 / count.max(1) as f32;
+                let lightness = if index.is_multiple_of(2) { 0.32 } else { 0.42 };
+                color_from_hsl((index as f32 * step) % 360.0, 0.85, lightness)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for KindPalette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KindPalette::Pastel => write!(f, "Pastel"),
+            KindPalette::ColorblindSafe => write!(f, "Colorblind safe"),
+            KindPalette::HighContrast => write!(f, "High contrast"),
         }
     }
 }
@@ -119,6 +216,85 @@ pub struct ThreadData {
     pub thread_root_mipmap: Option<ThreadGroupMipMap>,
     pub mipmaps: Vec<ThreadGroupMipMap>,
     pub max_depth: u32,
+    /// Events of this thread (excluding the thread root) ordered by start
+    /// time, with enclosing events before the events nested in them.
+    pub events: Vec<EventId>,
+}
+
+/// A move through the call tree of a thread, used for keyboard navigation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeDirection {
+    Parent,
+    FirstChild,
+    PreviousSibling,
+    NextSibling,
+}
+
+impl ThreadData {
+    // Locate `id` in `self.events` using the same ordering `assign_event_depths`
+    // sorts by.
+    fn event_position(&self, events: &[TimelineEvent], id: EventId) -> Option<usize> {
+        let key = |event: &TimelineEvent| {
+            (
+                event.start_ns,
+                std::cmp::Reverse(event.start_ns.saturating_add(event.duration_ns)),
+            )
+        };
+        let target = key(&events[id.index()]);
+        let first = self
+            .events
+            .partition_point(|other| key(&events[other.index()]) < target);
+        self.events[first..]
+            .iter()
+            .take_while(|other| key(&events[other.index()]) == target)
+            .position(|&other| other == id)
+            .map(|offset| first + offset)
+    }
+
+    /// The event reached from `id` by moving in `direction`. The thread root
+    /// only takes part when it is shown (`show_thread_root`).
+    pub fn tree_neighbor(
+        &self,
+        events: &[TimelineEvent],
+        id: EventId,
+        direction: TreeDirection,
+        show_thread_root: bool,
+    ) -> Option<EventId> {
+        if events[id.index()].is_thread_root {
+            return match direction {
+                TreeDirection::FirstChild => self.events.first().copied(),
+                _ => None,
+            };
+        }
+
+        let position = self.event_position(events, id)?;
+        let depth = events[id.index()].depth;
+        let depth_of = |other: &EventId| events[other.index()].depth;
+        match direction {
+            TreeDirection::Parent if depth == 0 => self.thread_root.filter(|_| show_thread_root),
+            TreeDirection::Parent => self.events[..position]
+                .iter()
+                .rev()
+                .find(|other| depth_of(other) < depth)
+                .copied(),
+            TreeDirection::FirstChild => self
+                .events
+                .get(position + 1)
+                .filter(|other| depth_of(other) == depth + 1)
+                .copied(),
+            TreeDirection::PreviousSibling => self.events[..position]
+                .iter()
+                .rev()
+                .find(|other| depth_of(other) <= depth)
+                .filter(|other| depth_of(other) == depth)
+                .copied(),
+            TreeDirection::NextSibling => self.events[position + 1..]
+                .iter()
+                .find(|other| depth_of(other) <= depth)
+                .filter(|other| depth_of(other) == depth)
+                .copied(),
+        }
+    }
 }
 
 // Event-related types are defined below in this file.
@@ -131,6 +307,192 @@ pub struct ThreadGroup {
     pub max_depth: u32,
     pub is_collapsed: bool,
     pub show_thread_roots: bool,
+    /// Kept at the top of the timeline, outside the vertical scroll.
+    pub is_pinned: bool,
+    pub is_hidden: bool,
+}
+
+impl ThreadGroup {
+    // Section of the group list: pinned groups come first, hidden ones last.
+    fn rank(&self) -> u8 {
+        if self.is_hidden {
+            2
+        } else if self.is_pinned {
+            0
+        } else {
+            1
+        }
+    }
+
+    fn start_ns(&self, events: &[TimelineEvent]) -> u64 {
+        self.threads
+            .iter()
+            .filter_map(|thread| thread.thread_root)
+            .map(|root| events[root.index()].start_ns)
+            .min()
+            .unwrap_or(u64::MAX)
+    }
+
+    fn min_thread_id(&self) -> u32 {
+        self.threads
+            .iter()
+            .map(|thread| thread.thread_id)
+            .min()
+            .unwrap_or(u32::MAX)
+    }
+}
+
+/// How threads are merged into shared groups of lanes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MergeStrategy {
+    /// Every thread gets its own group.
+    None,
+    /// Greedily pack threads with non-overlapping lifetimes, shortest first.
+    #[default]
+    Lifetime,
+    /// Pack only threads that spend most time in the same top-level label.
+    TopLevelLabel,
+    /// Groups merged by dragging one thread label onto another.
+    Manual,
+}
+
+impl MergeStrategy {
+    pub const ALL: [MergeStrategy; 4] = [
+        MergeStrategy::None,
+        MergeStrategy::Lifetime,
+        MergeStrategy::TopLevelLabel,
+        MergeStrategy::Manual,
+    ];
+
+    /// The strategy matching the on/off `merge_threads` setting of older
+    /// settings and session files.
+    pub fn from_merge_threads(merge_threads: bool) -> MergeStrategy {
+        if merge_threads {
+            MergeStrategy::Lifetime
+        } else {
+            MergeStrategy::None
+        }
+    }
+
+    /// Whether the timeline shows the merged groups rather than one group
+    /// per thread.
+    pub fn merges(self) -> bool {
+        self != MergeStrategy::None
+    }
+}
+
+impl std::fmt::Display for MergeStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MergeStrategy::None => write!(f, "No merging"),
+            MergeStrategy::Lifetime => write!(f, "By lifetime"),
+            MergeStrategy::TopLevelLabel => write!(f, "By top-level label"),
+            MergeStrategy::Manual => write!(f, "Manual"),
+        }
+    }
+}
+
+/// One-off orderings for the thread groups; the groups can be reordered by
+/// dragging afterwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreadSort {
+    Id,
+    StartTime,
+    BusyTime,
+    EventCount,
+}
+
+impl ThreadSort {
+    pub const ALL: [ThreadSort; 4] = [
+        ThreadSort::Id,
+        ThreadSort::StartTime,
+        ThreadSort::BusyTime,
+        ThreadSort::EventCount,
+    ];
+}
+
+impl std::fmt::Display for ThreadSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThreadSort::Id => write!(f, "Thread id"),
+            ThreadSort::StartTime => write!(f, "Start time"),
+            ThreadSort::BusyTime => write!(f, "Busy time"),
+            ThreadSort::EventCount => write!(f, "Event count"),
+        }
+    }
+}
+
+/// Restore the order of the sections after pinning or hiding groups: pinned
+/// groups first and hidden groups last, keeping the order within each.
+pub fn arrange_thread_groups(groups: &mut [ThreadGroup]) {
+    groups.sort_by_key(ThreadGroup::rank);
+}
+
+/// The pinned groups at the start of an arranged group list.
+pub fn pinned_thread_groups(groups: &[ThreadGroup]) -> &[ThreadGroup] {
+    let pinned = groups.iter().take_while(|group| group.rank() == 0).count();
+    &groups[..pinned]
+}
+
+/// The groups drawn in the vertically scrolled part of the timeline.
+pub fn scrolled_thread_groups(groups: &[ThreadGroup]) -> &[ThreadGroup] {
+    let pinned = pinned_thread_groups(groups).len();
+    let shown = groups.iter().take_while(|group| group.rank() < 2).count();
+    &groups[pinned..shown]
+}
+
+pub fn hidden_thread_group_count(groups: &[ThreadGroup]) -> usize {
+    groups.iter().filter(|group| group.is_hidden).count()
+}
+
+/// Sort the groups within their sections. Busy time and event count put the
+/// largest first.
+pub fn sort_thread_groups(
+    groups: &mut [ThreadGroup],
+    sort: ThreadSort,
+    events: &[TimelineEvent],
+    summaries: &HashMap<u32, crate::analysis::thread_summary::ThreadSummary>,
+) {
+    use crate::analysis::thread_summary::ThreadSummary;
+    match sort {
+        ThreadSort::Id => groups.sort_by_key(|group| (group.rank(), group.min_thread_id())),
+        ThreadSort::StartTime => {
+            groups.sort_by_key(|group| (group.rank(), group.start_ns(events)));
+        }
+        ThreadSort::BusyTime => groups.sort_by_key(|group| {
+            let busy_ns = ThreadSummary::of_group(summaries, group).busy_ns;
+            (group.rank(), std::cmp::Reverse(busy_ns))
+        }),
+        ThreadSort::EventCount => groups.sort_by_key(|group| {
+            let event_count = ThreadSummary::of_group(summaries, group).event_count;
+            (group.rank(), std::cmp::Reverse(event_count))
+        }),
+    }
+}
+
+/// Move the group `key` in front of `before`, or to the end of its section.
+pub fn move_thread_group(
+    groups: &mut Vec<ThreadGroup>,
+    key: ThreadGroupKey,
+    before: Option<ThreadGroupKey>,
+) {
+    let Some(from) = groups.iter().position(|group| thread_group_key(group) == key) else {
+        return;
+    };
+    let group = groups.remove(from);
+    let to = match before.and_then(|before| {
+        groups
+            .iter()
+            .position(|group| thread_group_key(group) == before)
+    }) {
+        Some(to) => to,
+        None => groups
+            .iter()
+            .take_while(|other| other.rank() <= group.rank())
+            .count(),
+    };
+    groups.insert(to, group);
+    arrange_thread_groups(groups);
 }
 
 #[derive(Debug, Clone)]
@@ -154,6 +516,23 @@ pub struct ShadowLevel {
     pub events_tree: IntervalTree<u64, ()>,
 }
 
+/// Whether any threads of the two groups are alive at the same time, so
+/// their events would overlap in shared lanes.
+pub fn thread_groups_overlap(events: &[TimelineEvent], a: &ThreadGroup, b: &ThreadGroup) -> bool {
+    a.threads.iter().any(|a| {
+        let (a_start, a_end) = thread_span(events, a);
+        b.threads.iter().any(|b| {
+            let (b_start, b_end) = thread_span(events, b);
+            a_start < b_end && b_start < a_end
+        })
+    })
+}
+
+/// Thread ids of a group, in lane order.
+pub fn group_thread_ids(group: &ThreadGroup) -> Vec<u32> {
+    group.threads.iter().map(|thread| thread.thread_id).collect()
+}
+
 pub fn thread_group_key(group: &ThreadGroup) -> ThreadGroupKey {
     Arc::as_ptr(&group.threads) as ThreadGroupKey
 }
@@ -173,6 +552,16 @@ pub struct FileData {
     pub timeline: TimelineData,
     pub events: Vec<TimelineEvent>,
     pub merged_thread_groups: Vec<ThreadGroup>,
+    pub utilization: crate::analysis::utilization::Utilization,
+    pub density: crate::analysis::density::ActivityDensity,
+    /// Event counts, busy time and activity of each thread, keyed by id.
+    pub thread_summaries: HashMap<u32, crate::analysis::thread_summary::ThreadSummary>,
+    /// Names given to threads by the user or by rules, keyed by id.
+    pub thread_names: HashMap<u32, String>,
+    /// Per-query accounting, ordered by descending execution time.
+    pub query_stats: Vec<QueryStats>,
+    pub query_blocking: crate::analysis::blocking::QueryBlocking,
+    pub phases: crate::analysis::phases::Phases,
     // Compact table of distinct event kinds with their assigned colors.
     pub kinds: Vec<KindInfo>,
     // Simple symbol interner for event strings so we store compact symbol ids
@@ -180,6 +569,86 @@ pub struct FileData {
     pub symbols: crate::symbols::Symbols,
 }
 
+impl FileData {
+    /// The name of a thread, or "Thread <id>" if it has none.
+    pub fn thread_name(&self, thread_id: u32) -> String {
+        thread_name(&self.thread_names, thread_id)
+    }
+}
+
+pub fn thread_name(names: &HashMap<u32, String>, thread_id: u32) -> String {
+    match names.get(&thread_id) {
+        Some(name) => name.clone(),
+        None => format!("Thread {}", thread_id),
+    }
+}
+
+// Event kinds recorded by rustc's self-profiler for query accounting.
+const QUERY_EVENT_KIND: &str = "Query";
+const QUERY_CACHE_HIT_EVENT_KIND: &str = "QueryCacheHit";
+const QUERY_CACHE_HIT_COUNT_EVENT_KIND: &str = "QueryCacheHitCount";
+const INCREMENTAL_LOAD_RESULT_EVENT_KIND: &str = "IncrementalLoadResult";
+
+/// Executions, cache hits and incremental loads of one query label.
+#[derive(Debug, Clone, Default)]
+pub struct QueryStats {
+    pub label: crate::symbols::Symbol,
+    pub executions: u64,
+    pub execution_ns: u64,
+    pub cache_hits: u64,
+    pub incremental_loads: u64,
+    pub incremental_load_ns: u64,
+}
+
+impl QueryStats {
+    /// Fraction of invocations (executions plus cache hits) answered from
+    /// the in-memory cache.
+    pub fn hit_rate(&self) -> Option<f64> {
+        let invocations = self.executions + self.cache_hits;
+        (invocations > 0).then(|| self.cache_hits as f64 / invocations as f64)
+    }
+
+    fn record(&mut self, kind: &str, payload: &analyzeme::EventPayload) {
+        match (kind, payload) {
+            (QUERY_EVENT_KIND, analyzeme::EventPayload::Timestamp(timestamp)) => {
+                self.executions += 1;
+                self.execution_ns += timestamp_duration_ns(timestamp);
+            }
+            (INCREMENTAL_LOAD_RESULT_EVENT_KIND, analyzeme::EventPayload::Timestamp(timestamp)) => {
+                self.incremental_loads += 1;
+                self.incremental_load_ns += timestamp_duration_ns(timestamp);
+            }
+            (QUERY_CACHE_HIT_EVENT_KIND, analyzeme::EventPayload::Timestamp(_)) => {
+                self.cache_hits += 1;
+            }
+            // Newer compilers only record the total hit count per query.
+            (QUERY_CACHE_HIT_COUNT_EVENT_KIND, analyzeme::EventPayload::Integer(count)) => {
+                self.cache_hits += count;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn timestamp_duration_ns(timestamp: &analyzeme::Timestamp) -> u64 {
+    match timestamp {
+        analyzeme::Timestamp::Interval { start, end } => {
+            end.duration_since(*start).unwrap_or_default().as_nanos() as u64
+        }
+        analyzeme::Timestamp::Instant(_) => 0,
+    }
+}
+
+fn is_query_kind(kind: &str) -> bool {
+    matches!(
+        kind,
+        QUERY_EVENT_KIND
+            | QUERY_CACHE_HIT_EVENT_KIND
+            | QUERY_CACHE_HIT_COUNT_EVENT_KIND
+            | INCREMENTAL_LOAD_RESULT_EVENT_KIND
+    )
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct KindInfo {
     pub kind: crate::symbols::Symbol,
@@ -192,7 +661,14 @@ pub struct FileUi {
     pub selected_event: Option<EventId>,
     pub hovered_event: Option<EventId>,
     pub hovered_event_position: Option<iced::Point>,
-    pub merge_threads: bool,
+    pub merge_strategy: MergeStrategy,
+    /// Thread ids of each group merged by hand, for `MergeStrategy::Manual`.
+    pub manual_merges: Vec<Vec<u32>>,
+    /// The last sort applied to the thread groups; cleared when a group is
+    /// moved by hand.
+    pub thread_sort: Option<ThreadSort>,
+    /// The thread being renamed and the name typed so far.
+    pub renaming_thread: Option<(u32, String)>,
     pub initial_fit_done: bool,
     pub view_type: crate::ViewType,
     // Use f64 for zoom/scroll state to avoid precision loss at high zoom.
@@ -202,6 +678,38 @@ pub struct FileUi {
     pub scroll_offset_y: f64,
     pub viewport_width: f64,
     pub viewport_height: f64,
+    /// Height of one depth lane in the timeline (vertical zoom).
+    pub lane_height: f64,
+    /// Width of the thread label column.
+    pub label_width: f64,
+    pub history: ViewHistory,
+    /// Events sharing the selected event's label, ordered by start time.
+    pub label_instances: Vec<EventId>,
+    /// Duration distribution of `label_instances`.
+    pub label_histogram: crate::analysis::histogram::DurationHistogram,
+    /// Histogram bucket picked in the details panel and its instances.
+    pub histogram_bucket: Option<u32>,
+    pub bucket_instances: Vec<EventId>,
+    /// The range picked with the measurement tool and its statistics.
+    pub measured_range: Option<crate::analysis::range::RangeStats>,
+    /// Computed when the idle gap view or shading is first needed.
+    pub idle_gaps: Option<crate::analysis::idle_gaps::IdleGaps>,
+    pub shade_idle_gaps: bool,
+    /// Computed when the outlier view or outlines are first needed.
+    pub outliers: Option<crate::analysis::outliers::Outliers>,
+    pub outline_outliers: bool,
+    pub query_sort: crate::analysis::queries::QuerySort,
+    pub show_all_query_links: bool,
+    /// Computed when the query key view is first shown.
+    pub query_keys: Option<crate::analysis::query_keys::QueryKeys>,
+    pub normalize_query_keys: bool,
+    pub key_grouping: crate::analysis::query_keys::KeyGrouping,
+    /// Computed when the codegen unit view is first shown.
+    pub cgu_timings: Option<crate::analysis::cgu::CguTimings>,
+    /// Computed when a color mode first needs it.
+    pub event_metrics: Option<crate::analysis::metrics::EventMetrics>,
+    /// The user's color rules resolved against this file.
+    pub rule_colors: crate::color_rules::RuleColors,
 }
 
 impl Default for FileUi {
@@ -211,7 +719,10 @@ impl Default for FileUi {
             selected_event: None,
             hovered_event: None,
             hovered_event_position: None,
-            merge_threads: true,
+            merge_strategy: MergeStrategy::default(),
+            manual_merges: Vec::new(),
+            thread_sort: None,
+            renaming_thread: None,
             initial_fit_done: false,
             view_type: crate::ViewType::default(),
             zoom_level: 1.0_f64,
@@ -219,6 +730,123 @@ impl Default for FileUi {
             scroll_offset_y: 0.0_f64,
             viewport_width: 0.0_f64,
             viewport_height: 0.0_f64,
+            lane_height: crate::timeline::LANE_HEIGHT,
+            label_width: crate::timeline::LABEL_WIDTH,
+            history: ViewHistory::default(),
+            label_instances: Vec::new(),
+            label_histogram: Default::default(),
+            histogram_bucket: None,
+            bucket_instances: Vec::new(),
+            measured_range: None,
+            idle_gaps: None,
+            shade_idle_gaps: false,
+            outliers: None,
+            outline_outliers: false,
+            query_sort: Default::default(),
+            show_all_query_links: false,
+            query_keys: None,
+            normalize_query_keys: false,
+            key_grouping: Default::default(),
+            cgu_timings: None,
+            event_metrics: None,
+            rule_colors: crate::color_rules::RuleColors::default(),
+        }
+    }
+}
+
+/// The zoom and scroll position of the timeline, as stored in the history.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewState {
+    pub zoom_level: f64,
+    pub scroll_offset_x: f64,
+    pub scroll_offset_y: f64,
+}
+
+const MAX_HISTORY_LEN: usize = 100;
+
+/// Consecutive continuous changes (panning, wheel zoom, scrollbars) closer
+/// together than this are recorded as a single history entry.
+const CONTINUOUS_NAVIGATION_GAP: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Back/forward stacks of timeline view states for one tab.
+#[derive(Debug, Clone, Default)]
+pub struct ViewHistory {
+    back: Vec<ViewState>,
+    forward: Vec<ViewState>,
+    last_continuous: Option<std::time::Instant>,
+}
+
+impl ViewHistory {
+    /// Record `current` before a discrete jump such as a zoom to a range.
+    pub fn record(&mut self, current: ViewState) {
+        self.last_continuous = None;
+        self.push(current);
+    }
+
+    /// Record `current` before a continuous change. Only the first change of
+    /// a burst creates an entry.
+    pub fn record_continuous(&mut self, current: ViewState) {
+        let now = std::time::Instant::now();
+        let in_burst = self
+            .last_continuous
+            .is_some_and(|last| now.duration_since(last) < CONTINUOUS_NAVIGATION_GAP);
+        self.last_continuous = Some(now);
+        if !in_burst {
+            self.push(current);
+        }
+    }
+
+    fn push(&mut self, current: ViewState) {
+        self.forward.clear();
+        if self.back.last() == Some(&current) {
+            return;
+        }
+        self.back.push(current);
+        if self.back.len() > MAX_HISTORY_LEN {
+            self.back.remove(0);
+        }
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        !self.back.is_empty()
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        !self.forward.is_empty()
+    }
+
+    pub fn go_back(&mut self, current: ViewState) -> Option<ViewState> {
+        let state = self.back.pop()?;
+        self.forward.push(current);
+        self.last_continuous = None;
+        Some(state)
+    }
+
+    pub fn go_forward(&mut self, current: ViewState) -> Option<ViewState> {
+        let state = self.forward.pop()?;
+        self.back.push(current);
+        self.last_continuous = None;
+        Some(state)
+    }
+}
+
+impl FileUi {
+    pub fn view_state(&self) -> ViewState {
+        ViewState {
+            zoom_level: self.zoom_level,
+            scroll_offset_x: self.scroll_offset_x,
+            scroll_offset_y: self.scroll_offset_y,
+        }
+    }
+
+    /// Initial UI state for a newly opened file, seeded from the user's
+    /// persistent preferences.
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        FileUi {
+            color_mode: config.color_mode,
+            merge_strategy: config.merge_strategy,
+            view_type: config.default_view,
+            ..FileUi::default()
         }
     }
 }
@@ -231,7 +859,280 @@ pub struct ProfileData {
     pub load_duration_ns: Option<u64>,
 }
 
-pub fn load_profiling_data(path: &Path) -> Result<ProfileData, String> {
+impl ProfileData {
+    /// Measure the absolute range `[start_ns, end_ns)`.
+    pub fn measure_range(&mut self, start_ns: u64, end_ns: u64) {
+        self.ui.measured_range = Some(crate::analysis::range::compute(
+            &self.data, start_ns, end_ns,
+        ));
+    }
+
+    /// Select `event` and collect the other instances of its label.
+    pub fn select_event(&mut self, event: Option<EventId>) {
+        let label_of = |id: Option<EventId>| {
+            id.and_then(|id| self.data.events.get(id.index()))
+                .filter(|event| !event.is_thread_root)
+                .map(|event| event.label)
+        };
+        let previous_label = label_of(self.ui.selected_event);
+        self.ui.selected_event = event;
+        // Moving between instances of one label keeps the picked histogram
+        // bucket.
+        if previous_label.is_some() && label_of(event) == previous_label {
+            return;
+        }
+        self.ui.label_instances.clear();
+        self.ui.label_histogram = Default::default();
+        self.select_histogram_bucket(None);
+        let Some(selected) = event.and_then(|id| self.data.events.get(id.index())) else {
+            return;
+        };
+        if selected.is_thread_root {
+            return;
+        }
+        let label = selected.label;
+        self.ui.label_instances = self
+            .data
+            .events
+            .par_iter()
+            .enumerate()
+            .filter(|(_, event)| event.label == label && !event.is_thread_root)
+            .map(|(index, _)| EventId(index as u32))
+            .collect();
+        let events = &self.data.events;
+        self.ui
+            .label_instances
+            .par_sort_by_key(|id| events[id.index()].start_ns);
+        self.ui.label_histogram = crate::analysis::histogram::DurationHistogram::compute(
+            events,
+            &self.ui.label_instances,
+        );
+    }
+
+    /// Highlight the instances of the selected label in a histogram bucket.
+    pub fn select_histogram_bucket(&mut self, bucket: Option<u32>) {
+        self.ui.histogram_bucket = bucket;
+        let events = &self.data.events;
+        self.ui.bucket_instances = match bucket {
+            Some(bucket) => self
+                .ui
+                .label_instances
+                .iter()
+                .copied()
+                .filter(|id| duration_bucket(events[id.index()].duration_ns) == bucket)
+                .collect(),
+            None => Vec::new(),
+        };
+    }
+
+    /// Compute the analyses needed by the current view that are missing or
+    /// out of date with `config`.
+    pub fn prepare_analysis(&mut self, config: &crate::config::Config) {
+        let needs_idle_gaps =
+            self.ui.view_type == crate::ViewType::IdleGaps || self.ui.shade_idle_gaps;
+        let threshold_ns = config.idle_gap_threshold_ns;
+        if needs_idle_gaps
+            && self.ui.idle_gaps.as_ref().map(|gaps| gaps.threshold_ns) != Some(threshold_ns)
+        {
+            self.ui.idle_gaps = Some(crate::analysis::idle_gaps::IdleGaps::compute(
+                &self.data,
+                threshold_ns,
+            ));
+        }
+
+        let needs_outliers =
+            self.ui.view_type == crate::ViewType::Outliers || self.ui.outline_outliers;
+        if needs_outliers
+            && self.ui.outliers.as_ref().map(|outliers| outliers.rule) != Some(config.outlier_rule)
+        {
+            let sort = self
+                .ui
+                .outliers
+                .as_ref()
+                .map(|outliers| outliers.sort)
+                .unwrap_or_default();
+            self.ui.outliers = Some(crate::analysis::outliers::Outliers::compute(
+                &self.data,
+                config.outlier_rule,
+                sort,
+            ));
+        }
+
+        let normalize = self.ui.normalize_query_keys;
+        if self.ui.view_type == crate::ViewType::QueryKeys
+            && self.ui.query_keys.as_ref().map(|keys| keys.normalized) != Some(normalize)
+        {
+            self.ui.query_keys = Some(crate::analysis::query_keys::QueryKeys::compute(
+                &self.data, normalize,
+            ));
+        }
+
+        if self.ui.view_type == crate::ViewType::CodegenUnits && self.ui.cgu_timings.is_none() {
+            self.ui.cgu_timings = Some(crate::analysis::cgu::CguTimings::compute(&self.data));
+        }
+
+        if self.ui.color_mode.needs_metrics() && self.ui.event_metrics.is_none() {
+            self.ui.event_metrics =
+                Some(crate::analysis::metrics::EventMetrics::compute(&self.data));
+        }
+    }
+
+    /// Resolve the user's color rules against this file's labels and kinds.
+    pub fn apply_color_rules(&mut self, rules: &crate::color_rules::ColorRules) {
+        self.ui.rule_colors =
+            rules.resolve(&self.data.events, &self.data.kinds, &self.data.symbols);
+    }
+
+    /// Name the threads and relabel their thread root events to match.
+    pub fn apply_thread_names(&mut self, names: HashMap<u32, String>) {
+        self.data.thread_names = names;
+        for group in &self.data.timeline.thread_groups {
+            for thread in group.threads.iter() {
+                if let Some(root) = thread.thread_root {
+                    let name = self.data.thread_name(thread.thread_id);
+                    self.data.events[root.index()].label = self.data.symbols.intern(&name);
+                }
+            }
+        }
+    }
+
+    /// Recolor the kinds table with `palette`.
+    pub fn apply_kind_palette(&mut self, palette: KindPalette) {
+        let count = self.data.kinds.len();
+        for (index, kind) in self.data.kinds.iter_mut().enumerate() {
+            kind.color = palette.color(index, count);
+        }
+    }
+
+    /// Rebuild the merged thread groups for the current merge strategy.
+    /// Groups that keep the same threads keep their place and state.
+    pub fn rebuild_merged_groups(&mut self) {
+        // The unmerged layout is kept separately; leave the merged groups
+        // as they were for when merging is turned back on.
+        if !self.ui.merge_strategy.merges() {
+            return;
+        }
+        let mut threads: Vec<Arc<ThreadData>> = self
+            .data
+            .timeline
+            .thread_groups
+            .iter()
+            .flat_map(|group| group.threads.iter().cloned())
+            .collect();
+        threads.sort_by_key(|thread| thread.thread_id);
+        let mut groups = build_merged_thread_groups(
+            &self.data.events,
+            &threads,
+            self.ui.merge_strategy,
+            &self.ui.manual_merges,
+        );
+
+        let previous = std::mem::take(&mut self.data.merged_thread_groups);
+        let previous_ids: Vec<Vec<u32>> = previous.iter().map(group_thread_ids).collect();
+        for group in &mut groups {
+            let ids = group_thread_ids(group);
+            if let Some(index) = previous_ids.iter().position(|other| *other == ids) {
+                group.is_collapsed = previous[index].is_collapsed;
+                group.is_pinned = previous[index].is_pinned;
+                group.is_hidden = previous[index].is_hidden;
+            }
+        }
+        // New groups take the place of the first old group sharing a thread.
+        groups.sort_by_key(|group| {
+            let ids = group_thread_ids(group);
+            previous_ids
+                .iter()
+                .position(|other| other.iter().any(|id| ids.contains(id)))
+                .unwrap_or(usize::MAX)
+        });
+        arrange_thread_groups(&mut groups);
+        self.data.merged_thread_groups = groups;
+    }
+
+    /// Switch the merge strategy. Manual merging starts out from the merged
+    /// groups on screen.
+    pub fn set_merge_strategy(&mut self, strategy: MergeStrategy) {
+        if strategy == MergeStrategy::Manual
+            && self.ui.manual_merges.is_empty()
+            && self.ui.merge_strategy.merges()
+        {
+            self.ui.manual_merges = self
+                .data
+                .merged_thread_groups
+                .iter()
+                .filter(|group| group.threads.len() > 1)
+                .map(group_thread_ids)
+                .collect();
+        }
+        self.ui.merge_strategy = strategy;
+        self.rebuild_merged_groups();
+    }
+
+    /// Merge the group `group` into `into` by hand. Groups with threads
+    /// alive at the same time are left apart.
+    pub fn merge_thread_groups(&mut self, group: ThreadGroupKey, into: ThreadGroupKey) {
+        let find = |key: ThreadGroupKey| {
+            self.data
+                .merged_thread_groups
+                .iter()
+                .find(|group| thread_group_key(group) == key)
+        };
+        let (Some(group), Some(into)) = (find(group), find(into)) else {
+            return;
+        };
+        if Arc::ptr_eq(&group.threads, &into.threads)
+            || thread_groups_overlap(&self.data.events, group, into)
+        {
+            return;
+        }
+        let (group, into) = (group_thread_ids(group), group_thread_ids(into));
+        self.ui
+            .manual_merges
+            .retain(|merge| *merge != group && *merge != into);
+        let mut merged = [group, into].concat();
+        merged.sort_unstable();
+        self.ui.manual_merges.push(merged);
+        self.rebuild_merged_groups();
+    }
+
+    /// Undo the manual merge that formed `group`.
+    pub fn split_thread_group(&mut self, group: ThreadGroupKey) {
+        let Some(ids) = self
+            .data
+            .merged_thread_groups
+            .iter()
+            .find(|other| thread_group_key(other) == group)
+            .map(group_thread_ids)
+        else {
+            return;
+        };
+        self.ui.manual_merges.retain(|merge| *merge != ids);
+        self.rebuild_merged_groups();
+    }
+
+    /// Apply the user's preferences to a freshly loaded file.
+    pub fn apply_config(&mut self, config: &crate::config::Config) {
+        self.ui = FileUi::from_config(config);
+        self.apply_kind_palette(config.kind_palette);
+        self.rebuild_merged_groups();
+        if config.collapse_threads {
+            for group in self
+                .data
+                .timeline
+                .thread_groups
+                .iter_mut()
+                .chain(self.data.merged_thread_groups.iter_mut())
+            {
+                group.is_collapsed = true;
+            }
+        }
+    }
+}
+
+pub fn load_profiling_data(
+    path: &Path,
+    phase_rules: &[crate::analysis::phases::PhaseRule],
+) -> Result<ProfileData, String> {
     let data = load_profiling_source(path)?;
     let metadata = data.metadata();
     let metadata_start_ns = metadata
@@ -271,7 +1172,30 @@ pub fn load_profiling_data(path: &Path) -> Result<ProfileData, String> {
     assign_event_depths(&mut events, &mut threads);
     let thread_data_vec = build_thread_data(&mut events, threads, &mut symbols);
     let thread_groups = build_thread_groups(&thread_data_vec);
-    let merged_thread_groups = build_merged_thread_groups(&events, &thread_data_vec);
+    let merged_thread_groups = build_merged_thread_groups(
+        &events,
+        &thread_data_vec,
+        MergeStrategy::default(),
+        &[],
+    );
+    let utilization =
+        crate::analysis::utilization::Utilization::compute(&events, &thread_data_vec);
+    let density =
+        crate::analysis::density::ActivityDensity::compute(&events, 0, collected.max_ns);
+    let thread_summaries = crate::analysis::thread_summary::ThreadSummary::compute_all(
+        &events,
+        &thread_data_vec,
+        collected.max_ns,
+    );
+    let phases = crate::analysis::phases::Phases::compute(
+        &events,
+        &thread_data_vec,
+        &symbols,
+        phase_rules,
+        collected.max_ns,
+    );
+    let query_blocking =
+        crate::analysis::blocking::QueryBlocking::compute(&events, &kinds, &symbols);
 
     Ok(ProfileData {
         data: FileData {
@@ -285,6 +1209,13 @@ pub fn load_profiling_data(path: &Path) -> Result<ProfileData, String> {
             },
             events,
             merged_thread_groups,
+            utilization,
+            density,
+            thread_summaries,
+            thread_names: HashMap::new(),
+            query_stats: collected.query_stats,
+            query_blocking,
+            phases,
             // store the precomputed kinds table for render-time lookup
             kinds,
             symbols,
@@ -302,6 +1233,9 @@ struct CollectedEvents {
     event_kinds: Vec<crate::symbols::Symbol>,
     max_ns: u64,
     event_count: usize,
+    /// Query accounting, including instant and integer events which are
+    /// not kept in `events`.
+    query_stats: Vec<QueryStats>,
 }
 
 fn load_profiling_source(path: &Path) -> Result<ProfilingData, String> {
@@ -321,11 +1255,23 @@ fn collect_timeline_events(
     let mut events = Vec::with_capacity(event_count);
     let mut max_ns: u64 = 0;
     let mut event_kinds: Vec<crate::symbols::Symbol> = Vec::with_capacity(event_count);
+    let mut query_stats: HashMap<crate::symbols::Symbol, QueryStats> = HashMap::new();
 
     for lightweight_event in data.iter() {
         let event = data.to_full_event(&lightweight_event);
         let thread_id = event.thread_id;
 
+        if is_query_kind(&event.event_kind) {
+            let label = symbols.intern(event.label.as_ref());
+            query_stats
+                .entry(label)
+                .or_insert_with(|| QueryStats {
+                    label,
+                    ..QueryStats::default()
+                })
+                .record(&event.event_kind, &event.payload);
+        }
+
         if let analyzeme::EventPayload::Timestamp(analyzeme::Timestamp::Interval { start, end }) =
             &event.payload
         {
@@ -376,11 +1322,15 @@ fn collect_timeline_events(
 
     events.shrink_to_fit();
 
+    let mut query_stats: Vec<QueryStats> = query_stats.into_values().collect();
+    query_stats.sort_by_key(|stats| std::cmp::Reverse(stats.execution_ns));
+
     CollectedEvents {
         events,
         event_kinds,
         max_ns,
         event_count,
+        query_stats,
     }
 }
 
@@ -399,14 +1349,11 @@ fn build_kind_table(
     kinds.sort_by_key(|s| symbols.resolve(*s));
 
     let kind_count = kinds.len().max(1);
-    let base_hue = 120.0_f32;
 
     let mut vec: Vec<KindInfo> = Vec::with_capacity(kind_count);
     let mut map: HashMap<crate::symbols::Symbol, usize> = HashMap::new();
     for (i, &kind_sym) in kinds.iter().enumerate() {
-        let step = 360.0 / kind_count as f32;
-        let hue = (base_hue + (i as f32) * step) % 360.0;
-        let color = color_from_hsl(hue, 0.35, 0.8);
+        let color = KindPalette::default().color(i, kind_count);
         vec.push(KindInfo {
             kind: kind_sym,
             color,
@@ -471,7 +1418,7 @@ fn assign_event_depths(events: &mut [TimelineEvent], threads: &mut HashMap<u32,
 
 fn build_thread_data(
     events: &mut Vec<TimelineEvent>,
-    threads: HashMap<u32, Vec<EventId>>,
+    mut threads: HashMap<u32, Vec<EventId>>,
     symbols: &mut crate::symbols::Symbols,
 ) -> Vec<Arc<ThreadData>> {
     let mut thread_data_vec = Vec::new();
@@ -570,6 +1517,7 @@ fn build_thread_data(
             thread_root_mipmap,
             mipmaps,
             max_depth,
+            events: threads.remove(&thread_id).unwrap_or_default(),
         }));
     }
 
@@ -586,35 +1534,39 @@ fn build_thread_groups(thread_data: &[Arc<ThreadData>]) -> Vec<ThreadGroup> {
             max_depth: thread.max_depth,
             is_collapsed: false,
             show_thread_roots: false,
+            is_pinned: false,
+            is_hidden: false,
         });
     }
 
     thread_groups
 }
 
-fn build_merged_thread_groups(
-    events: &[TimelineEvent],
-    threads: &[Arc<ThreadData>],
-) -> Vec<ThreadGroup> {
-    if threads.is_empty() {
-        return Vec::new();
+// Start and end of a thread's root event, or (0, 0) without one.
+fn thread_span(events: &[TimelineEvent], thread: &ThreadData) -> (u64, u64) {
+    match thread.thread_root {
+        Some(root) => {
+            let event = &events[root.index()];
+            (
+                event.start_ns,
+                event.start_ns.saturating_add(event.duration_ns),
+            )
+        }
+        None => (0, 0),
     }
+}
 
+// Pack threads into groups whose time ranges do not overlap, placing the
+// threads with the shortest lifetimes first. Threads come with their index in
+// the thread list, which orders the result.
+fn pack_threads(
+    events: &[TimelineEvent],
+    threads: Vec<(usize, Arc<ThreadData>)>,
+) -> Vec<Vec<(usize, Arc<ThreadData>)>> {
     let mut intervals: Vec<(usize, u64, u64, Arc<ThreadData>)> = threads
-        .iter()
-        .cloned()
-        .enumerate()
+        .into_iter()
         .map(|(index, thread)| {
-            let mut start = u64::MAX;
-            let mut end = 0u64;
-            if let Some(root) = thread.thread_root {
-                let event = &events[root.index()];
-                start = start.min(event.start_ns);
-                end = end.max(event.start_ns.saturating_add(event.duration_ns));
-            }
-            if start == u64::MAX {
-                start = 0;
-            }
+            let (start, end) = thread_span(events, &thread);
             (index, start, end, thread)
         })
         .collect();
@@ -647,6 +1599,86 @@ fn build_merged_thread_groups(
         }
     }
 
+    groups
+}
+
+// The top-level label a thread spends the most time in. Threads doing the
+// same kind of work, like LLVM workers, share it.
+fn dominant_top_level_label(
+    events: &[TimelineEvent],
+    thread: &ThreadData,
+) -> Option<crate::symbols::Symbol> {
+    // Total time and order of first appearance per label; ties go to the
+    // label seen first.
+    let mut time_by_label: HashMap<crate::symbols::Symbol, (u64, usize)> = HashMap::new();
+    for id in &thread.events {
+        let event = &events[id.index()];
+        if event.depth == 0 {
+            let order = time_by_label.len();
+            time_by_label.entry(event.label).or_insert((0, order)).0 += event.duration_ns;
+        }
+    }
+    time_by_label
+        .into_iter()
+        .max_by_key(|&(_, (time, order))| (time, std::cmp::Reverse(order)))
+        .map(|(label, _)| label)
+}
+
+pub(crate) fn build_merged_thread_groups(
+    events: &[TimelineEvent],
+    threads: &[Arc<ThreadData>],
+    strategy: MergeStrategy,
+    manual_merges: &[Vec<u32>],
+) -> Vec<ThreadGroup> {
+    if threads.is_empty() {
+        return Vec::new();
+    }
+
+    let indexed = threads.iter().cloned().enumerate();
+    let groups = match strategy {
+        MergeStrategy::None => indexed.map(|thread| vec![thread]).collect(),
+        MergeStrategy::Lifetime => pack_threads(events, indexed.collect()),
+        MergeStrategy::TopLevelLabel => {
+            // Threads of one label still need non-overlapping groups.
+            let mut by_label: Vec<(Option<crate::symbols::Symbol>, Vec<_>)> = Vec::new();
+            for (index, thread) in indexed {
+                let label = dominant_top_level_label(events, &thread);
+                match by_label.iter_mut().find(|(other, _)| *other == label) {
+                    Some((_, threads)) => threads.push((index, thread)),
+                    None => by_label.push((label, vec![(index, thread)])),
+                }
+            }
+            by_label
+                .into_iter()
+                .flat_map(|(_, threads)| pack_threads(events, threads))
+                .collect()
+        }
+        MergeStrategy::Manual => {
+            let mut groups: Vec<Vec<(usize, Arc<ThreadData>)>> = Vec::new();
+            let mut merge_of_group: Vec<Option<usize>> = Vec::new();
+            for (index, thread) in indexed {
+                let merge = manual_merges
+                    .iter()
+                    .position(|merge| merge.contains(&thread.thread_id));
+                match merge_of_group
+                    .iter()
+                    .position(|other| merge.is_some() && *other == merge)
+                {
+                    Some(group) => groups[group].push((index, thread)),
+                    None => {
+                        groups.push(vec![(index, thread)]);
+                        merge_of_group.push(merge);
+                    }
+                }
+            }
+            groups
+        }
+    };
+
+    thread_groups_from(groups)
+}
+
+fn thread_groups_from(mut groups: Vec<Vec<(usize, Arc<ThreadData>)>>) -> Vec<ThreadGroup> {
     groups.sort_by_key(|group| {
         group
             .iter()
@@ -678,6 +1710,8 @@ fn build_merged_thread_groups(
             max_depth,
             is_collapsed: false,
             show_thread_roots,
+            is_pinned: false,
+            is_hidden: false,
         });
     }
 
@@ -768,7 +1802,7 @@ fn build_event_indices(
     IntervalTree::from_iter(iter)
 }
 
-fn duration_bucket(duration_ns: u64) -> u32 {
+pub fn duration_bucket(duration_ns: u64) -> u32 {
     let duration = duration_ns.max(1);
     63u32 - duration.leading_zeros()
 }
@@ -881,8 +1915,8 @@ fn build_thread_group_mipmaps(
             // Re-inflate the previous shadows to the new min duration and merge
             // any overlaps introduced by the increased duration.
             let mut reinflated_by_depth: Vec<Vec<(u64, u64)>> = Vec::with_capacity(depth_count);
-            for depth in 0..depth_count {
-                let old = std::mem::take(&mut merged_by_depth[depth]);
+            for merged in merged_by_depth.iter_mut() {
+                let old = std::mem::take(merged);
                 let mut reinflated: Vec<(u64, u64)> = Vec::with_capacity(old.len());
                 for (start, end) in old {
                     let duration = end.saturating_sub(start).max(1);
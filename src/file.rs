@@ -1,5 +1,6 @@
 use crate::data::ProfileData;
 use crate::data::ThreadGroup;
+use crate::session::FileViewState;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone)]
@@ -7,6 +8,8 @@ pub struct FileTab {
     pub id: u64,
     pub path: PathBuf,
     pub load_state: FileLoadState,
+    /// View state from the previous session, applied once loading finishes.
+    pub restore_view: Option<FileViewState>,
 }
 
 #[derive(Debug, Clone)]
@@ -24,23 +27,40 @@ impl FileTab {
         }
     }
 
-    pub fn thread_groups(&self) -> &[ThreadGroup] {
+    /// All thread groups of the current layout, including pinned and hidden
+    /// ones.
+    pub fn all_thread_groups(&self) -> &[ThreadGroup] {
         let Some(stats) = self.stats() else {
             return &[];
         };
-        if stats.ui.merge_threads {
+        if stats.ui.merge_strategy.merges() {
             &stats.data.merged_thread_groups
         } else {
             &stats.data.timeline.thread_groups
         }
     }
 
-    pub fn thread_groups_mut(&mut self) -> Option<&mut [ThreadGroup]> {
+    /// The thread groups in the vertically scrolled part of the timeline.
+    pub fn thread_groups(&self) -> &[ThreadGroup] {
+        crate::data::scrolled_thread_groups(self.all_thread_groups())
+    }
+
+    pub fn pinned_thread_groups(&self) -> &[ThreadGroup] {
+        crate::data::pinned_thread_groups(self.all_thread_groups())
+    }
+
+    /// Lane height of the timeline, or the default before the file loads.
+    pub fn lane_height(&self) -> f64 {
+        self.stats()
+            .map_or(crate::timeline::LANE_HEIGHT, |stats| stats.ui.lane_height)
+    }
+
+    pub fn thread_groups_mut(&mut self) -> Option<&mut Vec<ThreadGroup>> {
         let stats = match &mut self.load_state {
             FileLoadState::Ready(stats) => stats.as_mut(),
             _ => return None,
         };
-        if stats.ui.merge_threads {
+        if stats.ui.merge_strategy.merges() {
             Some(&mut stats.data.merged_thread_groups)
         } else {
             Some(&mut stats.data.timeline.thread_groups)
@@ -1,18 +1,31 @@
 #![cfg_attr(target_os = "windows", windows_subsystem = "windows")]
 
+mod analysis;
+mod color_rules;
+mod config;
 mod data;
 mod file;
 mod scrollbar;
+mod session;
 mod settings;
 mod symbols;
+mod thread_names;
 mod timeline;
 mod tooltip;
 mod ui;
-use crate::data::EventId;
+use crate::analysis::outliers::{OutlierRule, OutlierSort};
+use crate::analysis::phases::PhaseRule;
+use crate::analysis::queries::QuerySort;
+use crate::analysis::query_keys::KeyGrouping;
+use crate::color_rules::ColorRules;
+use crate::config::{AppTheme, Config};
+use crate::data::{EventId, FileUi, ThreadGroup, TimelineEvent, TreeDirection};
 use crate::file::{FileLoadState, FileTab};
+use crate::session::{FileViewState, RecentFile, Session, SessionTab};
+use crate::thread_names::ThreadNameStore;
 use data::{ProfileData, format_panic_payload, load_profiling_data};
 use iced::futures::channel::oneshot;
-use iced::widget::{Space, button, checkbox, column, container, pick_list, row, scrollable, text};
+use iced::widget::{Space, button, column, container, pick_list, row, scrollable, slider, text};
 use iced::{Alignment, Element, Length, Task};
 use iced_aw::{TabLabel, tab_bar};
 use settings::{SettingsMessage, SettingsPage};
@@ -26,6 +39,9 @@ const SETTINGS_ICON: char = '\u{e8b8}';
 const OPEN_ICON: char = '\u{e2c7}';
 const FILE_ICON: char = '\u{e873}';
 const RESET_ICON: char = '\u{e5d5}';
+const BACK_ICON: char = '\u{e5c4}';
+const FORWARD_ICON: char = '\u{e5c8}';
+const SHOW_ICON: char = '\u{e8f4}';
 
 // Try to register the .mm_profdata extension to open with the current executable.
 // On Windows this writes under HKCU\Software\Classes so admin rights aren't required.
@@ -87,18 +103,36 @@ pub fn main() -> iced::Result {
         .font(include_bytes!("../assets/MaterialIcons-Regular.ttf"))
         .subscription(Lineme::subscription)
         .theme(Lineme::theme)
+        // Closing is handled in `Message::CloseRequested` so the session can be
+        // saved first.
+        .exit_on_close_request(false)
         .run()
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
 enum ViewType {
     Stats,
     #[default]
     Timeline,
+    IdleGaps,
+    Outliers,
+    Queries,
+    Blocking,
+    QueryKeys,
+    CodegenUnits,
 }
 
 impl ViewType {
-    const ALL: [ViewType; 2] = [ViewType::Stats, ViewType::Timeline];
+    const ALL: [ViewType; 8] = [
+        ViewType::Stats,
+        ViewType::Timeline,
+        ViewType::IdleGaps,
+        ViewType::Outliers,
+        ViewType::Queries,
+        ViewType::Blocking,
+        ViewType::QueryKeys,
+        ViewType::CodegenUnits,
+    ];
 }
 
 impl std::fmt::Display for ViewType {
@@ -106,6 +140,12 @@ impl std::fmt::Display for ViewType {
         match self {
             ViewType::Stats => write!(f, "Stats"),
             ViewType::Timeline => write!(f, "Timeline"),
+            ViewType::IdleGaps => write!(f, "Idle gaps"),
+            ViewType::Outliers => write!(f, "Outliers"),
+            ViewType::Queries => write!(f, "Queries"),
+            ViewType::Blocking => write!(f, "Query blocking"),
+            ViewType::QueryKeys => write!(f, "Query keys"),
+            ViewType::CodegenUnits => write!(f, "Codegen units"),
         }
     }
 }
@@ -120,7 +160,7 @@ fn neutral_pick_list_style(
     let palette = theme.extended_palette();
     let base_bg = palette.background.weak.color;
     let base_text = palette.background.weak.text;
-    let border_grey = iced::Color::from_rgb(0.8, 0.8, 0.8);
+    let border_grey = crate::ui::shade(theme, 0.2);
 
     match status {
         iced::widget::pick_list::Status::Active => iced::widget::pick_list::Style {
@@ -136,7 +176,7 @@ fn neutral_pick_list_style(
         },
         iced::widget::pick_list::Status::Hovered
         | iced::widget::pick_list::Status::Opened { .. } => {
-            let hover_bg = iced::Color::from_rgb(0.97, 0.97, 0.97);
+            let hover_bg = crate::ui::shade(theme, 0.03);
             iced::widget::pick_list::Style {
                 text_color: base_text,
                 placeholder_color: palette.secondary.base.color,
@@ -145,7 +185,7 @@ fn neutral_pick_list_style(
                 border: iced::Border {
                     radius: 3.0.into(),
                     width: 1.0,
-                    color: iced::Color::from_rgb(0.72, 0.72, 0.72),
+                    color: crate::ui::shade(theme, 0.28),
                 },
             }
         }
@@ -157,6 +197,7 @@ enum Message {
     TabSelected(usize),
     OpenFile,
     FileSelected(PathBuf),
+    RecentFileSelected(RecentFile),
     FileLoaded(u64, Box<ProfileData>, u64),
     FileLoadFailed(u64, String),
     ViewChanged(ViewType),
@@ -165,6 +206,8 @@ enum Message {
     OpenSettings,
     EventSelected(EventId),
     EventDoubleClicked(EventId),
+    /// Move the selection through the call tree (keyboard navigation).
+    SelectionMoved(TreeDirection),
     EventHovered {
         event: Option<EventId>,
         position: Option<iced::Point>,
@@ -197,15 +240,64 @@ enum Message {
     TimelineVerticalScrolled {
         scroll_y: f64,
     },
+    LaneHeightChanged(f64),
+    LabelWidthChanged(f64),
     TimelinePanned {
         delta: iced::Vector,
     },
     ResetView,
+    /// Measure an explicit ns range (start/end are ns relative to file min)
+    RangeSelected {
+        start_ns: f64,
+        end_ns: f64,
+    },
+    RangeCleared,
+    /// Switch to the timeline and zoom to an ns range (relative to file min),
+    /// selecting `event` if given. Used by the analysis views.
+    ShowRangeInTimeline {
+        start_ns: f64,
+        end_ns: f64,
+        event: Option<EventId>,
+    },
+    IdleGapThresholdChanged(u64),
+    ShadeIdleGapsToggled(bool),
+    /// Highlight the selected label's instances in a duration bucket.
+    HistogramBucketSelected(Option<u32>),
+    OutlierRuleChanged(OutlierRule),
+    OutlierSortChanged(OutlierSort),
+    OutlineOutliersToggled(bool),
+    QuerySortChanged(QuerySort),
+    ShowAllQueryLinksToggled(bool),
+    KeyGroupingChanged(KeyGrouping),
+    NormalizeQueryKeysToggled(bool),
+    NavigateBack,
+    NavigateForward,
     ToggleThreadCollapse(timeline::ThreadGroupKey),
+    /// Move a thread group in front of another, or to the end of its section.
+    MoveThreadGroup {
+        group: timeline::ThreadGroupKey,
+        before: Option<timeline::ThreadGroupKey>,
+    },
+    ToggleThreadGroupPinned(timeline::ThreadGroupKey),
+    HideThreadGroup(timeline::ThreadGroupKey),
+    ShowHiddenThreads,
+    ThreadSortChanged(data::ThreadSort),
+    /// Start editing the name of a thread.
+    RenameThread(u32),
+    ThreadNameEdited(String),
+    ThreadNameSubmitted,
+    RenameThreadCancelled,
     CollapseAllThreads,
     ExpandAllThreads,
-    MergeThreadsToggled(bool),
+    MergeStrategyChanged(data::MergeStrategy),
+    /// Merge one thread group into another by hand.
+    MergeThreadGroups {
+        group: timeline::ThreadGroupKey,
+        into: timeline::ThreadGroupKey,
+    },
+    SplitThreadGroup(timeline::ThreadGroupKey),
     ModifiersChanged(iced::keyboard::Modifiers),
+    CloseRequested,
 
     None,
     Settings(SettingsMessage),
@@ -217,44 +309,114 @@ struct Lineme {
     show_settings: bool,
     modifiers: iced::keyboard::Modifiers,
     settings: SettingsPage,
+    config: Config,
+    color_rules: ColorRules,
+    thread_names: ThreadNameStore,
+    session: Session,
     next_file_id: u64,
 }
 
 impl Lineme {
     fn new() -> (Self, Task<Message>) {
+        let mut settings = SettingsPage::new();
+        // A broken settings file should not prevent the app from starting;
+        // fall back to the defaults and surface the problem on the settings page.
+        // An unparsable file is moved aside first, so saving does not lose it.
+        let config = Config::load().unwrap_or_else(|error| {
+            settings.set_last_action_message(Some(error));
+            Config::default()
+        });
+        // Phase rules are only edited in the settings file, so report broken
+        // ones here rather than dropping them silently.
+        if let Err(error) = PhaseRule::validate(&config.phase_rules) {
+            settings.set_last_action_message(Some(error));
+        }
+        let session = Session::load().unwrap_or_else(|error| {
+            settings.set_last_action_message(Some(error));
+            Session::default()
+        });
+        let color_rules_source = ColorRules::load_source().unwrap_or_else(|error| {
+            settings.set_last_action_message(Some(error));
+            String::new()
+        });
+        // Rules that fail to parse stay in the editor next to their error, so
+        // applying them does not replace the file with an empty one.
+        settings.set_color_rules_text(&color_rules_source);
+        let color_rules = ColorRules::parse(&color_rules_source).unwrap_or_else(|error| {
+            settings.set_color_rules_error(Some(error));
+            ColorRules::default()
+        });
+        let thread_names = ThreadNameStore::load().unwrap_or_else(|error| {
+            settings.set_last_action_message(Some(error));
+            ThreadNameStore::default()
+        });
+
         let mut app = Lineme {
             active_tab: 0,
             files: Vec::new(),
             show_settings: false,
             modifiers: iced::keyboard::Modifiers::default(),
-            settings: SettingsPage::new(),
+            settings,
+            config,
+            color_rules,
+            thread_names,
+            session,
             next_file_id: 0,
         };
 
-        let initial_task = if let Some(path_str) = std::env::args().nth(1) {
+        let mut tasks = Vec::new();
+        if app.config.restore_session {
+            tasks.push(app.restore_session());
+        }
+        // A file given on the command line opens next to the restored tabs.
+        // The session is saved once it has loaded, so the previous tabs are
+        // never replaced by it alone.
+        if let Some(path_str) = std::env::args().nth(1) {
             let path = PathBuf::from(path_str);
-            app.start_loading_file(path)
-        } else {
-            Task::none()
-        };
+            match app.files.iter().position(|file| file.path == path) {
+                Some(index) => app.active_tab = index,
+                None => tasks.push(app.open_file_tab(path, None)),
+            }
+        }
 
-        (app, initial_task)
+        (app, Task::batch(tasks))
     }
 
     fn subscription(&self) -> iced::Subscription<Message> {
-        iced::event::listen_with(|event, _status, _id| match event {
+        iced::event::listen_with(|event, status, _id| match event {
             iced::Event::Window(iced::window::Event::FileDropped(path)) => {
                 Some(Message::FileSelected(path))
             }
+            iced::Event::Window(iced::window::Event::CloseRequested) => {
+                Some(Message::CloseRequested)
+            }
             // Track modifier changes for mouse-wheel & pan behavior
             iced::Event::Keyboard(iced::keyboard::Event::ModifiersChanged(modifiers)) => {
                 Some(Message::ModifiersChanged(modifiers))
             }
-            // Pressing Escape resets the current view (zoom/scroll)
+            // Pressing Escape resets the current view (zoom/scroll), unless a
+            // text input used it to give up focus.
             iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
                 key: iced::keyboard::Key::Named(iced::keyboard::key::Named::Escape),
                 ..
-            }) => Some(Message::ResetView),
+            }) if status == iced::event::Status::Ignored => Some(Message::ResetView),
+            // Alt+Left/Right and the mouse side buttons walk the view history
+            iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+                key: iced::keyboard::Key::Named(iced::keyboard::key::Named::ArrowLeft),
+                modifiers,
+                ..
+            }) if modifiers.alt() => Some(Message::NavigateBack),
+            iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+                key: iced::keyboard::Key::Named(iced::keyboard::key::Named::ArrowRight),
+                modifiers,
+                ..
+            }) if modifiers.alt() => Some(Message::NavigateForward),
+            iced::Event::Mouse(iced::mouse::Event::ButtonPressed(iced::mouse::Button::Back)) => {
+                Some(Message::NavigateBack)
+            }
+            iced::Event::Mouse(iced::mouse::Event::ButtonPressed(iced::mouse::Button::Forward)) => {
+                Some(Message::NavigateForward)
+            }
             iced::Event::Keyboard(_) => None,
             _ => None,
         })
@@ -282,17 +444,30 @@ impl Lineme {
         use iced::theme::Palette;
         use iced::Color;
 
-        iced::Theme::custom(
-            "Grey".to_string(),
-            Palette {
-                background: Color::WHITE,
-                text: Color::BLACK,
-                primary: Color::from_rgb(0.45, 0.45, 0.45),
-                success: Color::from_rgb(0.07, 0.40, 0.31),
-                warning: Color::from_rgb(0.72, 0.49, 0.20),
-                danger: Color::from_rgb(0.76, 0.26, 0.25),
-            },
-        )
+        match self.config.theme {
+            AppTheme::Light => iced::Theme::custom(
+                "Grey".to_string(),
+                Palette {
+                    background: Color::WHITE,
+                    text: Color::BLACK,
+                    primary: Color::from_rgb(0.45, 0.45, 0.45),
+                    success: Color::from_rgb(0.07, 0.40, 0.31),
+                    warning: Color::from_rgb(0.72, 0.49, 0.20),
+                    danger: Color::from_rgb(0.76, 0.26, 0.25),
+                },
+            ),
+            AppTheme::Dark => iced::Theme::custom(
+                "Dark grey".to_string(),
+                Palette {
+                    background: Color::from_rgb(0.12, 0.12, 0.13),
+                    text: Color::from_rgb(0.92, 0.92, 0.92),
+                    primary: Color::from_rgb(0.6, 0.6, 0.6),
+                    success: Color::from_rgb(0.35, 0.70, 0.55),
+                    warning: Color::from_rgb(0.88, 0.66, 0.35),
+                    danger: Color::from_rgb(0.90, 0.42, 0.40),
+                },
+            ),
+        }
     }
 
     fn update(&mut self, message: Message) -> Task<Message> {
@@ -300,12 +475,14 @@ impl Lineme {
             Message::TabSelected(index) => {
                 self.active_tab = index;
                 self.show_settings = false;
+                self.save_session_tabs();
 
                 if let Some(file) = self.files.get_mut(self.active_tab)
                     && let FileLoadState::Ready(stats) = &mut file.load_state
                 {
                     stats.ui.hovered_event = None;
                     stats.ui.hovered_event_position = None;
+                    stats.prepare_analysis(&self.config);
                 }
             }
             Message::OpenFile => {
@@ -328,11 +505,29 @@ impl Lineme {
             Message::FileSelected(path) => {
                 return self.start_loading_file(path);
             }
+            Message::RecentFileSelected(RecentFile(path)) => {
+                return self.start_loading_file(path);
+            }
             Message::FileLoaded(id, mut stats, duration_ns) => {
                 if let Some(file) = self.files.iter_mut().find(|file| file.id == id) {
                     // transfer load-duration into FileData and store ready state.
                     stats.load_duration_ns = Some(duration_ns);
+                    stats.apply_config(&self.config);
+                    stats.apply_color_rules(&self.color_rules);
+                    let names = thread_names::resolve(
+                        &self.config.thread_name_rules,
+                        self.thread_names.names(&file.path),
+                        &stats.data,
+                    );
+                    stats.apply_thread_names(names);
+                    if let Some(view) = file.restore_view.take() {
+                        view.apply(&mut stats);
+                    }
+                    stats.prepare_analysis(&self.config);
                     file.load_state = FileLoadState::Ready(stats);
+
+                    self.session.add_recent_file(&file.path);
+                    self.save_session_tabs();
                 }
             }
             Message::FileLoadFailed(id, error) => {
@@ -345,12 +540,16 @@ impl Lineme {
                     && let FileLoadState::Ready(stats) = &mut file.load_state
                 {
                     stats.ui.view_type = view;
+                    stats.prepare_analysis(&self.config);
                 }
             }
             Message::ColorModeChanged(color_mode) => {
                 if let Some(file) = self.files.get_mut(self.active_tab) {
                     match &mut file.load_state {
-                        FileLoadState::Ready(stats) => stats.ui.color_mode = color_mode,
+                        FileLoadState::Ready(stats) => {
+                            stats.ui.color_mode = color_mode;
+                            stats.prepare_analysis(&self.config);
+                        }
                         _ => {
                             // Keep color_mode in the UI until file loads; nothing to do here
                         }
@@ -363,6 +562,7 @@ impl Lineme {
                     if self.active_tab >= self.files.len() && !self.files.is_empty() {
                         self.active_tab = self.files.len() - 1;
                     }
+                    self.save_session_tabs();
                 }
 
                 if let Some(file) = self.files.get_mut(self.active_tab)
@@ -408,12 +608,71 @@ impl Lineme {
                 self.settings.set_last_action_message(Some(msg));
                 self.show_settings = true;
             }
+            Message::Settings(SettingsMessage::ColorModeChanged(color_mode)) => {
+                self.config.color_mode = color_mode;
+                self.save_config();
+            }
+            Message::Settings(SettingsMessage::MergeStrategyChanged(strategy)) => {
+                self.config.merge_strategy = strategy;
+                self.save_config();
+            }
+            Message::Settings(SettingsMessage::DefaultViewChanged(view)) => {
+                self.config.default_view = view;
+                self.save_config();
+            }
+            Message::Settings(SettingsMessage::ThemeChanged(theme)) => {
+                self.config.theme = theme;
+                self.save_config();
+            }
+            Message::Settings(SettingsMessage::KindPaletteChanged(palette)) => {
+                self.config.kind_palette = palette;
+                for file in &mut self.files {
+                    if let FileLoadState::Ready(stats) = &mut file.load_state {
+                        stats.apply_kind_palette(palette);
+                    }
+                }
+                self.save_config();
+            }
+            Message::Settings(SettingsMessage::CollapseThreadsToggled(collapse)) => {
+                self.config.collapse_threads = collapse;
+                self.save_config();
+            }
+            Message::Settings(SettingsMessage::RestoreSessionToggled(enabled)) => {
+                self.config.restore_session = enabled;
+                self.save_config();
+            }
+            Message::Settings(SettingsMessage::MiniTimelineUtilizationToggled(enabled)) => {
+                self.config.mini_timeline_utilization = enabled;
+                self.save_config();
+            }
+            Message::Settings(SettingsMessage::ColorRulesEdited(action)) => {
+                self.settings.edit_color_rules(action);
+            }
+            Message::Settings(SettingsMessage::ApplyColorRules) => {
+                match ColorRules::parse(&self.settings.color_rules_text()) {
+                    Ok(rules) => {
+                        for file in &mut self.files {
+                            if let FileLoadState::Ready(stats) = &mut file.load_state {
+                                stats.apply_color_rules(&rules);
+                            }
+                        }
+                        let result = rules.save();
+                        self.color_rules = rules;
+                        self.settings.set_color_rules_error(result.err());
+                    }
+                    Err(error) => self.settings.set_color_rules_error(Some(error)),
+                }
+            }
+            Message::Settings(SettingsMessage::ClearRecentFiles) => {
+                self.session.recent_files.clear();
+                self.save_session();
+            }
             Message::EventSelected(event) => {
                 if let Some(file) = self.files.get_mut(self.active_tab) {
                     match &mut file.load_state {
                         FileLoadState::Ready(stats) => {
                             let was_empty = stats.ui.selected_event.is_none();
-                            stats.ui.selected_event = Some(event);
+                            stats.select_event(Some(event));
                             if was_empty {
                                 return Task::none();
                             }
@@ -437,6 +696,8 @@ impl Lineme {
                         Some(event) => event,
                         None => return Task::none(),
                     };
+                    let current = stats.ui.view_state();
+                    stats.ui.history.record(current);
 
                     let min_ns = stats.data.timeline.min_ns;
                     let max_ns = stats.data.timeline.max_ns;
@@ -470,6 +731,68 @@ impl Lineme {
                     return Task::none();
                 }
             }
+            Message::SelectionMoved(direction) => {
+                if let Some(file) = self.files.get_mut(self.active_tab) {
+                    let stats = match &mut file.load_state {
+                        FileLoadState::Ready(stats) => stats,
+                        _ => return Task::none(),
+                    };
+                    let Some(selected) = stats.ui.selected_event else {
+                        return Task::none();
+                    };
+
+                    let events = &stats.data.events;
+                    let thread_groups = if stats.ui.merge_strategy.merges() {
+                        &mut stats.data.merged_thread_groups
+                    } else {
+                        &mut stats.data.timeline.thread_groups
+                    };
+                    let thread_id = events[selected.index()].thread_id;
+                    let Some(group_index) =
+                        timeline::group_index_of_thread(thread_groups, thread_id)
+                    else {
+                        return Task::none();
+                    };
+                    let group = &mut thread_groups[group_index];
+                    let Some(next) = group
+                        .threads
+                        .iter()
+                        .find(|thread| thread.thread_id == thread_id)
+                        .and_then(|thread| {
+                            thread.tree_neighbor(
+                                events,
+                                selected,
+                                direction,
+                                group.show_thread_roots,
+                            )
+                        })
+                    else {
+                        return Task::none();
+                    };
+
+                    // Expand a collapsed group so the new selection is visible.
+                    let next_event = &events[next.index()];
+                    if group.is_collapsed
+                        && data::display_depth(group.show_thread_roots, next_event) > 0
+                    {
+                        group.is_collapsed = false;
+                    }
+
+                    Lineme::scroll_event_into_view(
+                        &mut stats.ui,
+                        data::scrolled_thread_groups(thread_groups),
+                        next_event,
+                        stats.data.timeline.min_ns,
+                        stats.data.timeline.max_ns,
+                    );
+                    stats.select_event(Some(next));
+
+                    stats.ui.hovered_event = None;
+                    stats.ui.hovered_event_position = None;
+
+                    return Task::none();
+                }
+            }
             Message::EventHovered { event, position } => {
                 if let Some(file) = self.files.get_mut(self.active_tab) {
                     match &mut file.load_state {
@@ -489,6 +812,8 @@ impl Lineme {
                         FileLoadState::Ready(stats) => stats,
                         _ => return Task::none(),
                     };
+                    let current = stats.ui.view_state();
+                    stats.ui.history.record_continuous(current);
                     let min_ns = stats.data.timeline.min_ns;
                     let max_ns = stats.data.timeline.max_ns;
                     let zoom_factor = if delta > 0.0 { 1.1_f64 } else { 0.9_f64 };
@@ -523,7 +848,8 @@ impl Lineme {
             } => {
                 if let Some(file) = self.files.get_mut(self.active_tab) {
                     let thread_groups = file.thread_groups();
-                    let total_height = timeline::total_timeline_height(thread_groups);
+                    let total_height =
+                        timeline::total_timeline_height(thread_groups, file.lane_height());
 
                     let stats = match &mut file.load_state {
                         FileLoadState::Ready(stats) => stats,
@@ -580,6 +906,8 @@ impl Lineme {
                         FileLoadState::Ready(stats) => stats,
                         _ => return Task::none(),
                     };
+                    let current = stats.ui.view_state();
+                    stats.ui.history.record(current);
                     let min_ns = stats.data.timeline.min_ns;
                     let max_ns = stats.data.timeline.max_ns;
                     let total_ns = crate::timeline::total_ns(min_ns, max_ns);
@@ -619,6 +947,8 @@ impl Lineme {
                         FileLoadState::Ready(stats) => stats,
                         _ => return Task::none(),
                     };
+                    let current = stats.ui.view_state();
+                    stats.ui.history.record(current);
                     let min_ns = stats.data.timeline.min_ns;
                     let max_ns = stats.data.timeline.max_ns;
                     let provided_viewport_width = viewport_width.max(1.0) as f64;
@@ -658,6 +988,8 @@ impl Lineme {
                         FileLoadState::Ready(stats) => stats,
                         _ => return Task::none(),
                     };
+                    let current = stats.ui.view_state();
+                    stats.ui.history.record_continuous(current);
                     let zoom_level = stats.ui.zoom_level.max(1e-9);
                     let viewport_width = stats.ui.viewport_width.max(1.0);
                     let visible_ns = (viewport_width / zoom_level).max(1.0);
@@ -687,12 +1019,15 @@ impl Lineme {
             Message::TimelineVerticalScrolled { scroll_y } => {
                 if let Some(file) = self.files.get_mut(self.active_tab) {
                     let thread_groups = file.thread_groups();
-                    let total_height = timeline::total_timeline_height(thread_groups);
+                    let total_height =
+                        timeline::total_timeline_height(thread_groups, file.lane_height());
 
                     let stats = match &mut file.load_state {
                         FileLoadState::Ready(stats) => stats,
                         _ => return Task::none(),
                     };
+                    let current = stats.ui.view_state();
+                    stats.ui.history.record_continuous(current);
 
                     let viewport_height = stats.ui.viewport_height.max(1.0);
                     let max_scroll_y = (total_height - viewport_height).max(0.0);
@@ -714,6 +1049,8 @@ impl Lineme {
                         FileLoadState::Ready(stats) => stats,
                         _ => return Task::none(),
                     };
+                    let current = stats.ui.view_state();
+                    stats.ui.history.record(current);
                     let min_ns = stats.data.timeline.min_ns;
                     let max_ns = stats.data.timeline.max_ns;
                     let total_ns = crate::timeline::total_ns(min_ns, max_ns);
@@ -741,17 +1078,49 @@ impl Lineme {
                     return Task::none();
                 }
             }
+            Message::LaneHeightChanged(lane_height) => {
+                if let Some(file) = self.files.get_mut(self.active_tab) {
+                    let old_lane_height = file.lane_height();
+                    let lane_height =
+                        lane_height.clamp(timeline::MIN_LANE_HEIGHT, timeline::MAX_LANE_HEIGHT);
+                    if let FileLoadState::Ready(stats) = &mut file.load_state {
+                        stats.ui.lane_height = lane_height;
+                        // Keep roughly the same content at the top of the view.
+                        stats.ui.scroll_offset_y *= lane_height / old_lane_height;
+                    }
+                    let total_height =
+                        timeline::total_timeline_height(file.thread_groups(), lane_height);
+                    if let FileLoadState::Ready(stats) = &mut file.load_state {
+                        Lineme::clamp_vertical_scroll_if_needed(
+                            &mut stats.ui.scroll_offset_y,
+                            total_height,
+                            stats.ui.viewport_height,
+                        );
+                    }
+                }
+            }
+            Message::LabelWidthChanged(label_width) => {
+                if let Some(file) = self.files.get_mut(self.active_tab)
+                    && let FileLoadState::Ready(stats) = &mut file.load_state
+                {
+                    stats.ui.label_width =
+                        label_width.clamp(timeline::MIN_LABEL_WIDTH, timeline::MAX_LABEL_WIDTH);
+                }
+            }
             Message::TimelinePanned { delta } => {
                 if let Some(file) = self.files.get_mut(self.active_tab) {
                     // Get thread_groups and compute total height before taking a
                     // mutable borrow of file.load_state to avoid borrow conflicts.
                     let thread_groups = file.thread_groups();
-                    let total_height = timeline::total_timeline_height(thread_groups);
+                    let total_height =
+                        timeline::total_timeline_height(thread_groups, file.lane_height());
 
                     let stats = match &mut file.load_state {
                         FileLoadState::Ready(stats) => stats,
                         _ => return Task::none(),
                     };
+                    let current = stats.ui.view_state();
+                    stats.ui.history.record_continuous(current);
                     let min_ns = stats.data.timeline.min_ns;
                     let max_ns = stats.data.timeline.max_ns;
                     let total_ns = crate::timeline::total_ns(min_ns, max_ns);
@@ -781,6 +1150,8 @@ impl Lineme {
                         FileLoadState::Ready(stats) => stats,
                         _ => return Task::none(),
                     };
+                    let current = stats.ui.view_state();
+                    stats.ui.history.record(current);
                     let min_ns = stats.data.timeline.min_ns;
                     let max_ns = stats.data.timeline.max_ns;
                     let total_ns = max_ns.saturating_sub(min_ns);
@@ -800,9 +1171,197 @@ impl Lineme {
                     return Task::none();
                 }
             }
+            Message::NavigateBack | Message::NavigateForward => {
+                if let Some(file) = self.files.get_mut(self.active_tab) {
+                    let thread_groups = file.thread_groups();
+                    let total_height =
+                        timeline::total_timeline_height(thread_groups, file.lane_height());
+
+                    let stats = match &mut file.load_state {
+                        FileLoadState::Ready(stats) => stats,
+                        _ => return Task::none(),
+                    };
+                    let current = stats.ui.view_state();
+                    let target = if matches!(message, Message::NavigateBack) {
+                        stats.ui.history.go_back(current)
+                    } else {
+                        stats.ui.history.go_forward(current)
+                    };
+                    let Some(target) = target else {
+                        return Task::none();
+                    };
+
+                    let min_ns = stats.data.timeline.min_ns;
+                    let max_ns = stats.data.timeline.max_ns;
+                    let total_ns = crate::timeline::total_ns(min_ns, max_ns);
+                    stats.ui.zoom_level = target.zoom_level;
+                    stats.ui.initial_fit_done = true;
+                    stats.ui.scroll_offset_x = crate::timeline::clamp_scroll_offset_ns(
+                        target.scroll_offset_x,
+                        total_ns,
+                        stats.ui.viewport_width.max(0.0),
+                        stats.ui.zoom_level,
+                    );
+                    stats.ui.scroll_offset_y = target.scroll_offset_y;
+                    Lineme::clamp_vertical_scroll_if_needed(
+                        &mut stats.ui.scroll_offset_y,
+                        total_height,
+                        stats.ui.viewport_height,
+                    );
+
+                    stats.ui.hovered_event = None;
+                    stats.ui.hovered_event_position = None;
+
+                    return Task::none();
+                }
+            }
+            Message::RangeSelected { start_ns, end_ns } => {
+                if let Some(file) = self.files.get_mut(self.active_tab) {
+                    let stats = match &mut file.load_state {
+                        FileLoadState::Ready(stats) => stats,
+                        _ => return Task::none(),
+                    };
+                    let min_ns = stats.data.timeline.min_ns;
+                    let total_ns = timeline::total_ns(min_ns, stats.data.timeline.max_ns) as f64;
+                    let (start_ns, end_ns) = (start_ns.min(end_ns), start_ns.max(end_ns));
+                    let start_ns = start_ns.clamp(0.0, total_ns);
+                    let end_ns = end_ns.clamp(0.0, total_ns);
+                    stats.measure_range(
+                        min_ns.saturating_add(start_ns as u64),
+                        min_ns.saturating_add(end_ns as u64),
+                    );
+                }
+            }
+            Message::RangeCleared => {
+                if let Some(file) = self.files.get_mut(self.active_tab)
+                    && let FileLoadState::Ready(stats) = &mut file.load_state
+                {
+                    stats.ui.measured_range = None;
+                }
+            }
+            Message::ShowRangeInTimeline {
+                start_ns,
+                end_ns,
+                event,
+            } => {
+                if let Some(file) = self.files.get_mut(self.active_tab)
+                    && let FileLoadState::Ready(stats) = &mut file.load_state
+                {
+                    stats.ui.view_type = ViewType::Timeline;
+                }
+                let task = self.update(Message::TimelineZoomTo { start_ns, end_ns });
+                if let Some(event) = event
+                    && let Some(file) = self.files.get_mut(self.active_tab)
+                    && let FileLoadState::Ready(stats) = &mut file.load_state
+                {
+                    let thread_groups = if stats.ui.merge_strategy.merges() {
+                        &stats.data.merged_thread_groups
+                    } else {
+                        &stats.data.timeline.thread_groups
+                    };
+                    Lineme::scroll_event_into_view(
+                        &mut stats.ui,
+                        data::scrolled_thread_groups(thread_groups),
+                        &stats.data.events[event.index()],
+                        stats.data.timeline.min_ns,
+                        stats.data.timeline.max_ns,
+                    );
+                    stats.select_event(Some(event));
+                }
+                return task;
+            }
+            Message::IdleGapThresholdChanged(threshold_ns) => {
+                self.config.idle_gap_threshold_ns = threshold_ns;
+                self.save_config();
+                if let Some(file) = self.files.get_mut(self.active_tab)
+                    && let FileLoadState::Ready(stats) = &mut file.load_state
+                {
+                    stats.prepare_analysis(&self.config);
+                }
+            }
+            Message::HistogramBucketSelected(bucket) => {
+                if let Some(file) = self.files.get_mut(self.active_tab)
+                    && let FileLoadState::Ready(stats) = &mut file.load_state
+                {
+                    stats.select_histogram_bucket(bucket);
+                }
+            }
+            Message::OutlierRuleChanged(rule) => {
+                self.config.outlier_rule = rule;
+                self.save_config();
+                if let Some(file) = self.files.get_mut(self.active_tab)
+                    && let FileLoadState::Ready(stats) = &mut file.load_state
+                {
+                    stats.prepare_analysis(&self.config);
+                }
+            }
+            Message::OutlierSortChanged(sort) => {
+                if let Some(file) = self.files.get_mut(self.active_tab)
+                    && let FileLoadState::Ready(stats) = &mut file.load_state
+                    && let Some(outliers) = &mut stats.ui.outliers
+                {
+                    outliers.sort_by(&stats.data.events, &stats.data.symbols, sort);
+                }
+            }
+            Message::OutlineOutliersToggled(outline) => {
+                if let Some(file) = self.files.get_mut(self.active_tab)
+                    && let FileLoadState::Ready(stats) = &mut file.load_state
+                {
+                    stats.ui.outline_outliers = outline;
+                    stats.prepare_analysis(&self.config);
+                }
+            }
+            Message::QuerySortChanged(sort) => {
+                if let Some(file) = self.files.get_mut(self.active_tab)
+                    && let FileLoadState::Ready(stats) = &mut file.load_state
+                {
+                    stats.ui.query_sort = sort;
+                    analysis::queries::sort_queries(
+                        &mut stats.data.query_stats,
+                        &stats.data.symbols,
+                        sort,
+                    );
+                }
+            }
+            Message::ShowAllQueryLinksToggled(show) => {
+                if let Some(file) = self.files.get_mut(self.active_tab)
+                    && let FileLoadState::Ready(stats) = &mut file.load_state
+                {
+                    stats.ui.show_all_query_links = show;
+                }
+            }
+            Message::KeyGroupingChanged(grouping) => {
+                if let Some(file) = self.files.get_mut(self.active_tab)
+                    && let FileLoadState::Ready(stats) = &mut file.load_state
+                {
+                    stats.ui.key_grouping = grouping;
+                }
+            }
+            Message::NormalizeQueryKeysToggled(normalize) => {
+                if let Some(file) = self.files.get_mut(self.active_tab)
+                    && let FileLoadState::Ready(stats) = &mut file.load_state
+                {
+                    stats.ui.normalize_query_keys = normalize;
+                    stats.prepare_analysis(&self.config);
+                }
+            }
+            Message::ShadeIdleGapsToggled(shade) => {
+                if let Some(file) = self.files.get_mut(self.active_tab)
+                    && let FileLoadState::Ready(stats) = &mut file.load_state
+                {
+                    stats.ui.shade_idle_gaps = shade;
+                    stats.prepare_analysis(&self.config);
+                }
+            }
             Message::ModifiersChanged(modifiers) => {
                 self.modifiers = modifiers;
             }
+            Message::CloseRequested => {
+                self.capture_session_tabs();
+                // There is no UI left to report a failure to, so exit regardless.
+                let _ = self.session.save();
+                return iced::exit();
+            }
 
             Message::ToggleThreadCollapse(thread_id) => {
                 if let Some(file) = self.active_file_mut() {
@@ -818,7 +1377,8 @@ impl Lineme {
                     }
 
                     let thread_groups = file.thread_groups();
-                    let total_height = timeline::total_timeline_height(thread_groups);
+                    let total_height =
+                        timeline::total_timeline_height(thread_groups, file.lane_height());
                     let stats = match &mut file.load_state {
                         FileLoadState::Ready(stats) => stats,
                         _ => return Task::none(),
@@ -830,6 +1390,118 @@ impl Lineme {
                     );
                 }
             }
+            Message::MoveThreadGroup { group, before } => {
+                if let Some(file) = self.active_file_mut() {
+                    if let Some(groups) = file.thread_groups_mut() {
+                        data::move_thread_group(groups, group, before);
+                    }
+                    if let FileLoadState::Ready(stats) = &mut file.load_state {
+                        stats.ui.thread_sort = None;
+                    }
+                }
+            }
+            Message::ToggleThreadGroupPinned(key) => {
+                if let Some(file) = self.active_file_mut()
+                    && let Some(groups) = file.thread_groups_mut()
+                {
+                    if let Some(group) = groups
+                        .iter_mut()
+                        .find(|group| timeline::thread_group_key(group) == key)
+                    {
+                        group.is_pinned = !group.is_pinned;
+                    }
+                    data::arrange_thread_groups(groups);
+                    Lineme::clamp_file_vertical_scroll(file);
+                }
+            }
+            Message::HideThreadGroup(key) => {
+                if let Some(file) = self.active_file_mut()
+                    && let Some(groups) = file.thread_groups_mut()
+                {
+                    if let Some(group) = groups
+                        .iter_mut()
+                        .find(|group| timeline::thread_group_key(group) == key)
+                    {
+                        group.is_hidden = true;
+                    }
+                    data::arrange_thread_groups(groups);
+                    Lineme::clamp_file_vertical_scroll(file);
+                }
+            }
+            Message::ShowHiddenThreads => {
+                if let Some(file) = self.active_file_mut()
+                    && let Some(groups) = file.thread_groups_mut()
+                {
+                    for group in groups.iter_mut() {
+                        group.is_hidden = false;
+                    }
+                    data::arrange_thread_groups(groups);
+                }
+            }
+            Message::ThreadSortChanged(sort) => {
+                if let Some(file) = self.active_file_mut()
+                    && let FileLoadState::Ready(stats) = &mut file.load_state
+                {
+                    let thread_groups = if stats.ui.merge_strategy.merges() {
+                        &mut stats.data.merged_thread_groups
+                    } else {
+                        &mut stats.data.timeline.thread_groups
+                    };
+                    data::sort_thread_groups(
+                        thread_groups,
+                        sort,
+                        &stats.data.events,
+                        &stats.data.thread_summaries,
+                    );
+                    stats.ui.thread_sort = Some(sort);
+                }
+            }
+            Message::RenameThread(thread_id) => {
+                if let Some(file) = self.active_file_mut()
+                    && let FileLoadState::Ready(stats) = &mut file.load_state
+                {
+                    let name = stats
+                        .data
+                        .thread_names
+                        .get(&thread_id)
+                        .cloned()
+                        .unwrap_or_default();
+                    stats.ui.renaming_thread = Some((thread_id, name));
+                    return iced::widget::operation::focus(timeline::THREAD_NAME_INPUT);
+                }
+            }
+            Message::ThreadNameEdited(name) => {
+                if let Some(file) = self.active_file_mut()
+                    && let FileLoadState::Ready(stats) = &mut file.load_state
+                    && let Some((_, editing)) = &mut stats.ui.renaming_thread
+                {
+                    *editing = name;
+                }
+            }
+            Message::ThreadNameSubmitted => {
+                if let Some(file) = self.files.get_mut(self.active_tab)
+                    && let FileLoadState::Ready(stats) = &mut file.load_state
+                    && let Some((thread_id, name)) = stats.ui.renaming_thread.take()
+                {
+                    self.thread_names.set_name(&file.path, thread_id, &name);
+                    let names = thread_names::resolve(
+                        &self.config.thread_name_rules,
+                        self.thread_names.names(&file.path),
+                        &stats.data,
+                    );
+                    stats.apply_thread_names(names);
+                    if let Err(error) = self.thread_names.save() {
+                        self.settings.set_last_action_message(Some(error));
+                    }
+                }
+            }
+            Message::RenameThreadCancelled => {
+                if let Some(file) = self.active_file_mut()
+                    && let FileLoadState::Ready(stats) = &mut file.load_state
+                {
+                    stats.ui.renaming_thread = None;
+                }
+            }
             Message::CollapseAllThreads => {
                 if let Some(file) = self.active_file_mut() {
                     let thread_groups_mut = match file.thread_groups_mut() {
@@ -841,7 +1513,8 @@ impl Lineme {
                     }
 
                     let thread_groups = file.thread_groups();
-                    let total_height = timeline::total_timeline_height(thread_groups);
+                    let total_height =
+                        timeline::total_timeline_height(thread_groups, file.lane_height());
                     let stats = match &mut file.load_state {
                         FileLoadState::Ready(stats) => stats,
                         _ => return Task::none(),
@@ -864,7 +1537,8 @@ impl Lineme {
                     }
 
                     let thread_groups = file.thread_groups();
-                    let total_height = timeline::total_timeline_height(thread_groups);
+                    let total_height =
+                        timeline::total_timeline_height(thread_groups, file.lane_height());
                     let stats = match &mut file.load_state {
                         FileLoadState::Ready(stats) => stats,
                         _ => return Task::none(),
@@ -876,24 +1550,31 @@ impl Lineme {
                     );
                 }
             }
-            Message::MergeThreadsToggled(enabled) => {
+            Message::MergeStrategyChanged(strategy) => {
                 if let Some(file) = self.active_file_mut() {
-                    // Update merge_threads on loaded FileData if present.
                     if let FileLoadState::Ready(stats) = &mut file.load_state {
-                        stats.ui.merge_threads = enabled;
+                        stats.set_merge_strategy(strategy);
                     }
-
-                    let thread_groups = file.thread_groups();
-                    let total_height = timeline::total_timeline_height(thread_groups);
-                    let stats = match &mut file.load_state {
-                        FileLoadState::Ready(stats) => stats,
-                        _ => return Task::none(),
-                    };
-                    Lineme::clamp_vertical_scroll_if_needed(
-                        &mut stats.ui.scroll_offset_y,
-                        total_height,
-                        stats.ui.viewport_height,
-                    );
+                    Lineme::clamp_file_vertical_scroll(file);
+                }
+                // Remember the choice for the next files.
+                self.config.merge_strategy = strategy;
+                self.save_config();
+            }
+            Message::MergeThreadGroups { group, into } => {
+                if let Some(file) = self.active_file_mut() {
+                    if let FileLoadState::Ready(stats) = &mut file.load_state {
+                        stats.merge_thread_groups(group, into);
+                    }
+                    Lineme::clamp_file_vertical_scroll(file);
+                }
+            }
+            Message::SplitThreadGroup(group) => {
+                if let Some(file) = self.active_file_mut() {
+                    if let FileLoadState::Ready(stats) = &mut file.load_state {
+                        stats.split_thread_group(group);
+                    }
+                    Lineme::clamp_file_vertical_scroll(file);
                 }
             }
             Message::None => {}
@@ -902,6 +1583,17 @@ impl Lineme {
     }
 
     fn start_loading_file(&mut self, path: PathBuf) -> Task<Message> {
+        let task = self.open_file_tab(path, None);
+        self.save_session_tabs();
+        task
+    }
+
+    // Open a tab loading `path`, restoring `restore_view` once it has loaded.
+    fn open_file_tab(
+        &mut self,
+        path: PathBuf,
+        restore_view: Option<FileViewState>,
+    ) -> Task<Message> {
         let id = self.next_file_id;
         self.next_file_id = self.next_file_id.wrapping_add(1);
 
@@ -909,16 +1601,19 @@ impl Lineme {
             id,
             path: path.clone(),
             load_state: FileLoadState::Loading,
+            restore_view,
         });
         self.active_tab = self.files.len() - 1;
         self.show_settings = false;
 
+        let phase_rules = self.config.phase_rules.clone();
         Task::perform(
             async move {
                 let (tx, rx) = oneshot::channel();
                 thread::spawn(move || {
                     let start = Instant::now();
-                    let result = std::panic::catch_unwind(|| load_profiling_data(&path));
+                    let result =
+                        std::panic::catch_unwind(|| load_profiling_data(&path, &phase_rules));
                     let outcome = match result {
                         Ok(result) => result,
                         Err(payload) => Err(format_panic_payload(payload)),
@@ -940,11 +1635,121 @@ impl Lineme {
         )
     }
 
+    // Reopen the tabs saved by the previous run, restoring their view state
+    // once each file has loaded.
+    fn restore_session(&mut self) -> Task<Message> {
+        let tabs = self.session.tabs.clone();
+        let active_tab = self.session.active_tab;
+        let mut tasks = Vec::new();
+        for tab in tabs {
+            tasks.push(self.open_file_tab(tab.path, tab.view));
+        }
+        if active_tab < self.files.len() {
+            self.active_tab = active_tab;
+        }
+        Task::batch(tasks)
+    }
+
+    // Record the open tabs and their view state in the session.
+    fn capture_session_tabs(&mut self) {
+        self.session.tabs = self
+            .files
+            .iter()
+            .map(|file| SessionTab {
+                path: file.path.clone(),
+                view: file
+                    .stats()
+                    .map(FileViewState::capture)
+                    .or_else(|| file.restore_view.clone()),
+            })
+            .collect();
+        self.session.active_tab = self.active_tab;
+    }
+
+    // Save the open tabs right away, so they survive a crash.
+    fn save_session_tabs(&mut self) {
+        self.capture_session_tabs();
+        self.save_session();
+    }
+
+    // Persist recent files and open tabs, reporting failures on the settings page.
+    fn save_session(&mut self) {
+        if let Err(error) = self.session.save() {
+            self.settings.set_last_action_message(Some(error));
+        }
+    }
+
+    // Persist the current settings, reporting failures on the settings page.
+    fn save_config(&mut self) {
+        if let Err(error) = self.config.save() {
+            self.settings.set_last_action_message(Some(error));
+        }
+    }
+
     // Convenience accessor for the currently active file (mutable).
     fn active_file_mut(&mut self) -> Option<&mut FileTab> {
         self.files.get_mut(self.active_tab)
     }
 
+    // Clamp the vertical scroll after groups were pinned, hidden or merged.
+    fn clamp_file_vertical_scroll(file: &mut FileTab) {
+        let total_height =
+            timeline::total_timeline_height(file.thread_groups(), file.lane_height());
+        if let FileLoadState::Ready(stats) = &mut file.load_state {
+            Lineme::clamp_vertical_scroll_if_needed(
+                &mut stats.ui.scroll_offset_y,
+                total_height,
+                stats.ui.viewport_height,
+            );
+        }
+    }
+
+    // Scroll the timeline just enough to show `event`, keeping the zoom level.
+    // Events wider than the viewport are aligned to their start.
+    fn scroll_event_into_view(
+        ui: &mut FileUi,
+        thread_groups: &[ThreadGroup],
+        event: &TimelineEvent,
+        min_ns: u64,
+        max_ns: u64,
+    ) {
+        let zoom_level = ui.zoom_level.max(1e-9);
+        let viewport_width = ui.viewport_width.max(1.0);
+        let visible_ns = viewport_width / zoom_level;
+        let margin_ns = visible_ns * 0.05;
+        let start_ns = event.start_ns.saturating_sub(min_ns) as f64;
+        let end_ns = start_ns + event.duration_ns as f64;
+
+        let view_start = ui.scroll_offset_x;
+        let view_end = view_start + visible_ns;
+        if start_ns < view_start || end_ns - start_ns > visible_ns {
+            ui.scroll_offset_x = start_ns - margin_ns;
+        } else if end_ns > view_end {
+            ui.scroll_offset_x = end_ns - visible_ns + margin_ns;
+        }
+        ui.scroll_offset_x = crate::timeline::clamp_scroll_offset_ns(
+            ui.scroll_offset_x,
+            timeline::total_ns(min_ns, max_ns),
+            viewport_width,
+            zoom_level,
+        );
+
+        let lane_height = ui.lane_height;
+        if let Some(lane_y) = timeline::event_lane_y(thread_groups, event, lane_height) {
+            let viewport_height = ui.viewport_height.max(lane_height);
+            if lane_y < ui.scroll_offset_y {
+                ui.scroll_offset_y = lane_y;
+            } else if lane_y + lane_height > ui.scroll_offset_y + viewport_height {
+                ui.scroll_offset_y = lane_y + lane_height - viewport_height;
+            }
+            Lineme::clamp_vertical_scroll_if_needed(
+                &mut ui.scroll_offset_y,
+                timeline::total_timeline_height(thread_groups, lane_height),
+                ui.viewport_height,
+            );
+        }
+    }
+
     // Helper used after operations that can change the total vertical height of
     // the timeline (collapse/expand, merge threads, ...). If the current
     // vertical scroll is beyond the new total height, clamp it.
@@ -1043,6 +1848,20 @@ impl Lineme {
                 )
                 .style(crate::ui::neutral_button_style)
                 .on_press(Message::OpenFile),
+                pick_list(
+                    self.session
+                        .recent_files
+                        .iter()
+                        .cloned()
+                        .map(RecentFile)
+                        .collect::<Vec<_>>(),
+                    None::<RecentFile>,
+                    Message::RecentFileSelected,
+                )
+                .placeholder("Recent")
+                .text_size(12)
+                .padding(3)
+                .style(neutral_pick_list_style),
                 // Settings button acts as a toggle. When active, show a highlighted background.
                 button(text(SETTINGS_ICON).font(ICON_FONT).size(18))
                     .style(|theme: &iced::Theme, status: button::Status| {
@@ -1064,25 +1883,78 @@ impl Lineme {
             .padding(5)
             .align_y(Alignment::Center),
         )
-        .style(|_theme: &iced::Theme| {
-            // Use explicit greys for a consistent look regardless of theme.
+        .style(|theme: &iced::Theme| {
+            // A neutral band of the theme's greys.
             container::Style::default()
-                .background(iced::Color::from_rgb(0.95, 0.95, 0.95))
+                .background(crate::ui::shade(theme, 0.05))
                 .border(iced::Border {
-                    color: iced::Color::from_rgb(0.8, 0.8, 0.8),
+                    color: crate::ui::shade(theme, 0.2),
                     width: 1.0,
                     ..Default::default()
                 })
         });
 
         let content: Element<'_, Message> = if self.show_settings {
-            self.settings.view().map(Message::Settings)
+            self.settings.view(&self.config).map(Message::Settings)
         } else if let Some(file) = self.files.get(self.active_tab) {
             // Use view_type from FileData when available; fall back to default
             let current_view = file.stats().map(|s| s.ui.view_type).unwrap_or_default();
             let inner_view = match current_view {
                 ViewType::Stats => self.file_view(file),
                 ViewType::Timeline => self.timeline_view(file),
+                ViewType::IdleGaps => match file.stats() {
+                    Some(stats) => match &stats.ui.idle_gaps {
+                        Some(gaps) => {
+                            analysis::idle_gaps::view(&stats.data, gaps, stats.ui.shade_idle_gaps)
+                        }
+                        None => self.file_view(file),
+                    },
+                    None => self.file_view(file),
+                },
+                ViewType::Outliers => match file.stats() {
+                    Some(stats) => match &stats.ui.outliers {
+                        Some(outliers) => analysis::outliers::view(
+                            &stats.data,
+                            outliers,
+                            stats.ui.outline_outliers,
+                        ),
+                        None => self.file_view(file),
+                    },
+                    None => self.file_view(file),
+                },
+                ViewType::Queries => match file.stats() {
+                    Some(stats) => analysis::queries::view(&stats.data, stats.ui.query_sort),
+                    None => self.file_view(file),
+                },
+                ViewType::Blocking => match file.stats() {
+                    Some(stats) => analysis::blocking::view(analysis::blocking::BlockingViewArgs {
+                        blocking: &stats.data.query_blocking,
+                        events: &stats.data.events,
+                        symbols: &stats.data.symbols,
+                        thread_names: &stats.data.thread_names,
+                        min_ns: stats.data.timeline.min_ns,
+                        show_all_links: stats.ui.show_all_query_links,
+                    }),
+                    None => self.file_view(file),
+                },
+                ViewType::QueryKeys => match file.stats() {
+                    Some(stats) => match &stats.ui.query_keys {
+                        Some(keys) => {
+                            analysis::query_keys::view(&stats.data, keys, stats.ui.key_grouping)
+                        }
+                        None => self.file_view(file),
+                    },
+                    None => self.file_view(file),
+                },
+                ViewType::CodegenUnits => match file.stats() {
+                    Some(stats) => match &stats.ui.cgu_timings {
+                        Some(timings) => {
+                            analysis::cgu::view(&stats.data, timings, &stats.ui.rule_colors)
+                        }
+                        None => self.file_view(file),
+                    },
+                    None => self.file_view(file),
+                },
             };
 
             if matches!(file.load_state, FileLoadState::Ready(_)) {
@@ -1098,6 +1970,7 @@ impl Lineme {
                 .spacing(5)
                 .align_y(Alignment::Center);
 
+                let hidden_threads = data::hidden_thread_group_count(file.all_thread_groups());
                 let right_controls: Element<'_, Message> = if current_view == ViewType::Timeline {
                     Element::from(
                         row![
@@ -1111,11 +1984,63 @@ impl Lineme {
                             .text_size(12)
                             .padding(3)
                             .style(neutral_pick_list_style),
-                            checkbox(file.stats().map(|s| s.ui.merge_threads).unwrap_or(false))
-                                .label("Merge threads")
-                                .size(14)
-                                .text_size(12)
-                                .on_toggle(Message::MergeThreadsToggled),
+                            text("Merge threads:").size(12),
+                            pick_list(
+                                &data::MergeStrategy::ALL[..],
+                                file.stats().map(|s| s.ui.merge_strategy),
+                                Message::MergeStrategyChanged,
+                            )
+                            .text_size(12)
+                            .padding(3)
+                            .style(neutral_pick_list_style),
+                            pick_list(
+                                &data::ThreadSort::ALL[..],
+                                file.stats().and_then(|s| s.ui.thread_sort),
+                                Message::ThreadSortChanged,
+                            )
+                            .placeholder("Sort threads")
+                            .text_size(12)
+                            .padding(3)
+                            .style(neutral_pick_list_style),
+                            button(
+                                row![
+                                    text(SHOW_ICON).font(ICON_FONT),
+                                    text(format!("Show hidden ({})", hidden_threads)).size(12.0)
+                                ]
+                                .spacing(5)
+                                .align_y(Alignment::Center),
+                            )
+                            .style(crate::ui::toolbar_button_style)
+                            .padding(3)
+                            .on_press_maybe(
+                                (hidden_threads > 0).then_some(Message::ShowHiddenThreads)
+                            ),
+                            text("Lane height:").size(12),
+                            slider(
+                                timeline::MIN_LANE_HEIGHT..=timeline::MAX_LANE_HEIGHT,
+                                file.lane_height(),
+                                Message::LaneHeightChanged,
+                            )
+                            .step(1.0)
+                            .width(Length::Fixed(90.0)),
+                            row![
+                                button(text(BACK_ICON).font(ICON_FONT))
+                                    .style(crate::ui::toolbar_button_style)
+                                    .padding(3)
+                                    .on_press_maybe(
+                                        file.stats()
+                                            .filter(|s| s.ui.history.can_go_back())
+                                            .map(|_| Message::NavigateBack)
+                                    ),
+                                button(text(FORWARD_ICON).font(ICON_FONT))
+                                    .style(crate::ui::toolbar_button_style)
+                                    .padding(3)
+                                    .on_press_maybe(
+                                        file.stats()
+                                            .filter(|s| s.ui.history.can_go_forward())
+                                            .map(|_| Message::NavigateForward)
+                                    ),
+                            ],
                             button(
                                 row![
                                     text(RESET_ICON).font(ICON_FONT),
@@ -1124,22 +2049,7 @@ impl Lineme {
                                 .spacing(5)
                                 .align_y(Alignment::Center),
                             )
-                            .style(|theme: &iced::Theme, status: button::Status| {
-                                let palette = theme.extended_palette();
-                                let base = button::Style {
-                                    text_color: palette.background.base.text,
-                                    ..Default::default()
-                                };
-                                match status {
-                                    button::Status::Hovered | button::Status::Pressed => {
-                                        button::Style {
-                                            background: Some(palette.background.weak.color.into()),
-                                            ..base
-                                        }
-                                    }
-                                    _ => base,
-                                }
-                            })
+                            .style(crate::ui::toolbar_button_style)
                             .padding(3)
                             .on_press(Message::ResetView),
                         ]
@@ -1161,12 +2071,12 @@ impl Lineme {
                     .align_y(Alignment::Center),
                 )
                 .width(Length::Fill)
-                .style(|_theme: &iced::Theme| {
+                .style(|theme: &iced::Theme| {
                     // Make the selector container a neutral grey to match the header.
                     container::Style::default()
-                        .background(iced::Color::from_rgb(0.95, 0.95, 0.95))
+                        .background(crate::ui::shade(theme, 0.05))
                         .border(iced::Border {
-                            color: iced::Color::from_rgb(0.8, 0.8, 0.8),
+                            color: crate::ui::shade(theme, 0.2),
                             width: 1.0,
                             ..Default::default()
                         })
@@ -1179,12 +2089,7 @@ impl Lineme {
                 inner_view
             }
         } else {
-            container(text("Open a file to start").size(20))
-                .width(Length::Fill)
-                .height(Length::Fill)
-                .center_x(Length::Fill)
-                .center_y(Length::Fill)
-                .into()
+            self.start_view()
         };
 
         let root = column![header, content].height(Length::Fill);
@@ -1211,13 +2116,15 @@ impl Lineme {
                     crate::timeline::format_duration(event.duration_ns);
 
                 let content = row![
-                    text(duration_str).size(12).style(|_t: &iced::Theme| text::Style {
-                        color: Some(iced::Color::from_rgb(0.408, 0.322, 0.459)),
-                        ..Default::default()
+                    text(duration_str).size(12).style(|theme: &iced::Theme| text::Style {
+                        color: Some(if theme.extended_palette().is_dark {
+                            iced::Color::from_rgb(0.75, 0.64, 0.82)
+                        } else {
+                            iced::Color::from_rgb(0.408, 0.322, 0.459)
+                        }),
                     }),
-                    text(label).size(12).style(|_t: &iced::Theme| text::Style {
-                        color: Some(iced::Color::from_rgb(0.15, 0.15, 0.15)),
-                        ..Default::default()
+                    text(label).size(12).style(|theme: &iced::Theme| text::Style {
+                        color: Some(crate::ui::shade(theme, 0.85)),
                     }),
                 ]
                 .spacing(8)
@@ -1238,6 +2145,39 @@ impl Lineme {
             .into()
     }
 
+    // Shown when no file is open: a prompt plus the recently opened files.
+    fn start_view(&self) -> Element<'_, Message> {
+        let mut start = column![text("Open a file to start").size(20)]
+            .spacing(8)
+            .align_x(Alignment::Center);
+
+        if !self.session.recent_files.is_empty() {
+            start = start.push(Space::new().height(8)).push(text("Recent files").size(14));
+            for path in &self.session.recent_files {
+                let recent = RecentFile(path.clone());
+                start = start.push(
+                    button(
+                        column![
+                            text(recent.to_string()).size(12),
+                            text(format!("{}", path.display())).size(10),
+                        ]
+                        .spacing(2),
+                    )
+                    .style(crate::ui::neutral_button_style)
+                    .width(Length::Fixed(420.0))
+                    .on_press(Message::RecentFileSelected(recent)),
+                );
+            }
+        }
+
+        container(start)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill)
+            .into()
+    }
+
     fn file_view<'a>(&self, file: &'a FileTab) -> Element<'a, Message> {
         let stats_col = match &file.load_state {
             FileLoadState::Loading => column![
@@ -1301,19 +2241,7 @@ impl Lineme {
         let inner = container(stats_col)
             .width(Length::Fill)
             .padding(12)
-            .style(|theme: &iced::Theme| {
-                // Match details panel style from timeline: use the theme's background
-                // base color and a subtle strong-color border so the panel reads as
-                // a cohesive block in the layout.
-                let palette = theme.extended_palette();
-                container::Style::default()
-                    .background(palette.background.base.color)
-                    .border(iced::Border {
-                        color: palette.background.strong.color,
-                        width: 1.0,
-                        ..Default::default()
-                    })
-            });
+            .style(crate::ui::panel_style);
 
         let scroll = scrollable::Scrollable::new(inner)
             .width(Length::Fill)
@@ -1347,14 +2275,42 @@ impl Lineme {
                 timeline_data: &stats.data.timeline,
                 events: &stats.data.events,
                 thread_groups: file.thread_groups(),
+                pinned_thread_groups: file.pinned_thread_groups(),
                 kinds: &stats.data.kinds,
                 zoom_level: stats.ui.zoom_level,
                 selected_event: &stats.ui.selected_event,
+                label_instances: &stats.ui.label_instances,
+                label_histogram: &stats.ui.label_histogram,
+                histogram_bucket: stats.ui.histogram_bucket,
+                bucket_instances: &stats.ui.bucket_instances,
+                measured_range: stats.ui.measured_range.as_ref(),
+                idle_gaps: match &stats.ui.idle_gaps {
+                    Some(gaps) if stats.ui.shade_idle_gaps => &gaps.gaps,
+                    _ => &[],
+                },
+                outliers: match &stats.ui.outliers {
+                    Some(outliers) if stats.ui.outline_outliers => &outliers.outliers,
+                    _ => &[],
+                },
+                query_blocking: &stats.data.query_blocking,
+                phases: &stats.data.phases,
+                show_all_query_links: stats.ui.show_all_query_links,
+                utilization: &stats.data.utilization,
+                density: &stats.data.density,
+                mini_timeline_utilization: self.config.mini_timeline_utilization,
                 scroll_offset_x: stats.ui.scroll_offset_x,
                 scroll_offset_y: stats.ui.scroll_offset_y,
                 viewport_width: stats.ui.viewport_width,
                 viewport_height: stats.ui.viewport_height,
+                lane_height: stats.ui.lane_height,
+                label_width: stats.ui.label_width,
+                thread_summaries: &stats.data.thread_summaries,
+                thread_names: &stats.data.thread_names,
+                manual_merge: stats.ui.merge_strategy == data::MergeStrategy::Manual,
+                renaming_thread: stats.ui.renaming_thread.as_ref(),
                 color_mode: stats.ui.color_mode,
+                rule_colors: &stats.ui.rule_colors,
+                event_metrics: stats.ui.event_metrics.as_ref(),
                 symbols: &stats.data.symbols,
             }),
         }
@@ -1,3 +1,4 @@
+use crate::ui::shade;
 use iced::mouse;
 use iced::widget::canvas::{self, Action, Canvas, Geometry, Program};
 use iced::{Element, Event, Length, Point, Rectangle, Renderer, Theme, Vector};
@@ -235,7 +236,7 @@ impl<'a, Message> Program<Message> for ScrollbarProgram<'a, Message> {
         &self,
         _state: &Self::State,
         renderer: &Renderer,
-        _theme: &Theme,
+        theme: &Theme,
         bounds: Rectangle,
         _cursor: mouse::Cursor,
     ) -> Vec<Geometry> {
@@ -267,20 +268,23 @@ impl<'a, Message> Program<Message> for ScrollbarProgram<'a, Message> {
         frame.fill_rectangle(
             track_rect.position(),
             track_rect.size(),
-            iced::Color::from_rgb(0.92, 0.92, 0.92),
+            shade(theme, 0.08),
         );
 
         let thumb = self.thumb_bounds(bounds);
         frame.fill_rectangle(
             thumb.position(),
             thumb.size(),
-            iced::Color::from_rgb(0.75, 0.75, 0.75),
+            shade(theme, 0.25),
         );
 
         frame.stroke(
             &canvas::Path::rectangle(thumb.position(), thumb.size()),
             canvas::Stroke::default()
-                .with_color(iced::Color::from_rgba(0.0, 0.0, 0.0, 0.2))
+                .with_color(iced::Color {
+                    a: 0.2,
+                    ..shade(theme, 1.0)
+                })
                 .with_width(1.0),
         );
 
@@ -322,14 +326,12 @@ impl<'a, Message> Program<Message> for ScrollbarProgram<'a, Message> {
             Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
                 state.dragging = false;
             }
-            Event::Mouse(mouse::Event::CursorMoved { position }) => {
-                if state.dragging {
-                    let clamped = self.clamp_local_position(bounds, *position);
-                    let local = clamped - Vector::new(bounds.x, bounds.y);
-                    let value = self
-                        .value_from_local_axis(bounds, self.local_axis(local) - state.drag_offset);
-                    return Some(Action::publish((self.on_change)(value)).and_capture());
-                }
+            Event::Mouse(mouse::Event::CursorMoved { position }) if state.dragging => {
+                let clamped = self.clamp_local_position(bounds, *position);
+                let local = clamped - Vector::new(bounds.x, bounds.y);
+                let value =
+                    self.value_from_local_axis(bounds, self.local_axis(local) - state.drag_offset);
+                return Some(Action::publish((self.on_change)(value)).and_capture());
             }
             _ => {}
         }
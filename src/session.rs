@@ -0,0 +1,265 @@
+//! Recently opened files and the open tabs of the previous run.
+//!
+//! This is application state rather than user preferences, so it is kept in
+//! its own `session.toml` next to the settings file.
+
+use crate::ViewType;
+use crate::data::{
+    ColorMode, EventId, MergeStrategy, ProfileData, ThreadGroup, group_thread_ids,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const SESSION_FILE_NAME: &str = "session.toml";
+const MAX_RECENT_FILES: usize = 10;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// Most recently opened first.
+    pub recent_files: Vec<PathBuf>,
+    pub tabs: Vec<SessionTab>,
+    pub active_tab: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTab {
+    pub path: PathBuf,
+    /// View state of the tab; `None` if the file had not finished loading.
+    pub view: Option<FileViewState>,
+}
+
+/// The restorable part of `FileUi` plus the collapsed state of thread groups.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FileViewState {
+    pub view_type: ViewType,
+    pub color_mode: ColorMode,
+    pub merge_strategy: MergeStrategy,
+    /// Thread ids of each group merged by hand.
+    pub manual_merges: Vec<Vec<u32>>,
+    pub zoom_level: f64,
+    pub scroll_offset_x: f64,
+    pub scroll_offset_y: f64,
+    pub lane_height: f64,
+    pub label_width: f64,
+    pub selected_event: Option<u32>,
+    /// Thread ids of each collapsed group in the unmerged layout.
+    pub collapsed_groups: Vec<Vec<u32>>,
+    /// Thread ids of each collapsed group in the merged layout.
+    pub collapsed_merged_groups: Vec<Vec<u32>>,
+    pub group_layout: GroupLayout,
+    pub merged_group_layout: GroupLayout,
+    /// Replaced by `merge_strategy`; only read from older files.
+    #[serde(skip_serializing)]
+    merge_threads: Option<bool>,
+}
+
+/// Order, pinned and hidden groups of one thread layout, each group given by
+/// its thread ids.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GroupLayout {
+    pub order: Vec<Vec<u32>>,
+    pub pinned: Vec<Vec<u32>>,
+    pub hidden: Vec<Vec<u32>>,
+}
+
+impl GroupLayout {
+    fn capture(groups: &[ThreadGroup]) -> Self {
+        let ids_where = |keep: fn(&ThreadGroup) -> bool| {
+            groups
+                .iter()
+                .filter(|group| keep(group))
+                .map(group_thread_ids)
+                .collect()
+        };
+        GroupLayout {
+            order: groups.iter().map(group_thread_ids).collect(),
+            pinned: ids_where(|group| group.is_pinned),
+            hidden: ids_where(|group| group.is_hidden),
+        }
+    }
+
+    fn apply(&self, groups: &mut [ThreadGroup]) {
+        // Groups missing from the saved order keep their place after the
+        // known ones.
+        groups.sort_by_key(|group| {
+            let ids = group_thread_ids(group);
+            self.order
+                .iter()
+                .position(|order| *order == ids)
+                .unwrap_or(usize::MAX)
+        });
+        for group in groups.iter_mut() {
+            let ids = group_thread_ids(group);
+            group.is_pinned = self.pinned.contains(&ids);
+            group.is_hidden = self.hidden.contains(&ids);
+        }
+        crate::data::arrange_thread_groups(groups);
+    }
+}
+
+impl Default for FileViewState {
+    fn default() -> Self {
+        FileViewState {
+            view_type: ViewType::default(),
+            color_mode: ColorMode::default(),
+            merge_strategy: MergeStrategy::default(),
+            manual_merges: Vec::new(),
+            zoom_level: 1.0,
+            scroll_offset_x: 0.0,
+            scroll_offset_y: 0.0,
+            lane_height: crate::timeline::LANE_HEIGHT,
+            label_width: crate::timeline::LABEL_WIDTH,
+            selected_event: None,
+            collapsed_groups: Vec::new(),
+            collapsed_merged_groups: Vec::new(),
+            group_layout: GroupLayout::default(),
+            merged_group_layout: GroupLayout::default(),
+            merge_threads: None,
+        }
+    }
+}
+
+fn collapsed_thread_ids(groups: &[ThreadGroup]) -> Vec<Vec<u32>> {
+    groups
+        .iter()
+        .filter(|group| group.is_collapsed)
+        .map(group_thread_ids)
+        .collect()
+}
+
+fn restore_collapsed(groups: &mut [ThreadGroup], collapsed: &[Vec<u32>]) {
+    for group in groups {
+        let ids = group_thread_ids(group);
+        group.is_collapsed = collapsed.contains(&ids);
+    }
+}
+
+impl FileViewState {
+    pub fn capture(stats: &ProfileData) -> Self {
+        FileViewState {
+            view_type: stats.ui.view_type,
+            color_mode: stats.ui.color_mode,
+            merge_strategy: stats.ui.merge_strategy,
+            manual_merges: stats.ui.manual_merges.clone(),
+            zoom_level: stats.ui.zoom_level,
+            scroll_offset_x: stats.ui.scroll_offset_x,
+            scroll_offset_y: stats.ui.scroll_offset_y,
+            lane_height: stats.ui.lane_height,
+            label_width: stats.ui.label_width,
+            selected_event: stats.ui.selected_event.map(|id| id.0),
+            collapsed_groups: collapsed_thread_ids(&stats.data.timeline.thread_groups),
+            collapsed_merged_groups: collapsed_thread_ids(&stats.data.merged_thread_groups),
+            group_layout: GroupLayout::capture(&stats.data.timeline.thread_groups),
+            merged_group_layout: GroupLayout::capture(&stats.data.merged_thread_groups),
+            merge_threads: None,
+        }
+    }
+
+    /// Apply the saved state to a freshly loaded file. Values that do not fit
+    /// the file (e.g. if it changed on disk) are ignored.
+    pub fn apply(&self, stats: &mut ProfileData) {
+        stats.ui.view_type = self.view_type;
+        stats.ui.color_mode = self.color_mode;
+        stats.ui.merge_strategy = match self.merge_threads {
+            Some(merge_threads) => MergeStrategy::from_merge_threads(merge_threads),
+            None => self.merge_strategy,
+        };
+        stats.ui.manual_merges = self.manual_merges.clone();
+        stats.rebuild_merged_groups();
+        if self.lane_height.is_finite() {
+            stats.ui.lane_height = self
+                .lane_height
+                .clamp(crate::timeline::MIN_LANE_HEIGHT, crate::timeline::MAX_LANE_HEIGHT);
+        }
+        if self.label_width.is_finite() {
+            stats.ui.label_width = self
+                .label_width
+                .clamp(crate::timeline::MIN_LABEL_WIDTH, crate::timeline::MAX_LABEL_WIDTH);
+        }
+        if self.zoom_level.is_finite() && self.zoom_level > 0.0 {
+            stats.ui.zoom_level = self.zoom_level;
+            stats.ui.scroll_offset_x = self.scroll_offset_x.max(0.0);
+            stats.ui.scroll_offset_y = self.scroll_offset_y.max(0.0);
+            // Keep the restored zoom instead of fitting the whole profile
+            // once the viewport size is known.
+            stats.ui.initial_fit_done = true;
+        }
+        let selected_event = self
+            .selected_event
+            .map(EventId)
+            .filter(|id| id.index() < stats.data.events.len());
+        stats.select_event(selected_event);
+        restore_collapsed(
+            &mut stats.data.timeline.thread_groups,
+            &self.collapsed_groups,
+        );
+        restore_collapsed(
+            &mut stats.data.merged_thread_groups,
+            &self.collapsed_merged_groups,
+        );
+        self.group_layout.apply(&mut stats.data.timeline.thread_groups);
+        self.merged_group_layout.apply(&mut stats.data.merged_thread_groups);
+    }
+}
+
+/// Path of the session file, if the platform has a config directory.
+pub fn session_path() -> Option<PathBuf> {
+    crate::config::config_dir().map(|dir| dir.join(SESSION_FILE_NAME))
+}
+
+impl Session {
+    /// Load the session file. A missing file yields an empty session; one
+    /// that cannot be parsed is moved aside, so saving does not lose it.
+    pub fn load() -> Result<Session, String> {
+        let Some(path) = session_path() else {
+            return Ok(Session::default());
+        };
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Session::default());
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                return Err(crate::config::parse_error(&path, e));
+            }
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        toml::from_str(&contents).map_err(|e| crate::config::parse_error(&path, e))
+    }
+
+    /// Write the session file, creating the config directory if needed.
+    pub fn save(&self) -> Result<(), String> {
+        let path = session_path().ok_or_else(|| "No config directory available".to_string())?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let contents = toml::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize session: {}", e))?;
+        std::fs::write(&path, contents)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Move `path` to the front of the recent files list.
+    pub fn add_recent_file(&mut self, path: &Path) {
+        self.recent_files.retain(|recent| recent != path);
+        self.recent_files.insert(0, path.to_path_buf());
+        self.recent_files.truncate(MAX_RECENT_FILES);
+    }
+}
+
+/// A recent file entry for the header pick list, displayed by file name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecentFile(pub PathBuf);
+
+impl std::fmt::Display for RecentFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0.file_name() {
+            Some(name) => write!(f, "{}", name.to_string_lossy()),
+            None => write!(f, "{}", self.0.display()),
+        }
+    }
+}
@@ -1,22 +1,43 @@
-use crate::{FILE_ICON, ICON_FONT};
-use iced::widget::{button, column, container, row, scrollable, text, Space};
+use crate::config::{AppTheme, Config};
+use crate::data::{ColorMode, KindPalette, MergeStrategy};
+use crate::{FILE_ICON, ICON_FONT, ViewType, neutral_pick_list_style};
+use iced::widget::{
+    button, checkbox, column, container, pick_list, row, scrollable, text, text_editor, Space,
+};
 use iced::{Alignment, Element, Length};
 
 #[derive(Debug, Clone)]
 pub enum SettingsMessage {
     RegisterFileExtension,
     RegisterFileExtensionResult(Result<(), String>),
+    ColorModeChanged(ColorMode),
+    MergeStrategyChanged(MergeStrategy),
+    DefaultViewChanged(ViewType),
+    ThemeChanged(AppTheme),
+    KindPaletteChanged(KindPalette),
+    CollapseThreadsToggled(bool),
+    RestoreSessionToggled(bool),
+    MiniTimelineUtilizationToggled(bool),
+    ClearRecentFiles,
+    ColorRulesEdited(text_editor::Action),
+    ApplyColorRules,
 }
 
 #[derive(Debug, Default)]
 pub struct SettingsPage {
     last_action_message: Option<String>,
+    /// Unapplied text of the color rules editor.
+    color_rules: text_editor::Content,
+    /// Why the color rules could not be applied or saved.
+    color_rules_error: Option<String>,
 }
 
 impl SettingsPage {
     pub fn new() -> Self {
         Self {
             last_action_message: None,
+            color_rules: text_editor::Content::new(),
+            color_rules_error: None,
         }
     }
 
@@ -24,7 +45,147 @@ impl SettingsPage {
         self.last_action_message = message;
     }
 
-    pub fn view(&self) -> Element<'_, SettingsMessage> {
+    pub fn set_color_rules_text(&mut self, text: &str) {
+        self.color_rules = text_editor::Content::with_text(text);
+    }
+
+    pub fn color_rules_text(&self) -> String {
+        self.color_rules.text()
+    }
+
+    pub fn edit_color_rules(&mut self, action: text_editor::Action) {
+        self.color_rules.perform(action);
+    }
+
+    pub fn set_color_rules_error(&mut self, error: Option<String>) {
+        self.color_rules_error = error;
+    }
+
+    pub fn view(&self, config: &Config) -> Element<'_, SettingsMessage> {
+        let preferences = column![
+            text("Preferences").size(16),
+            row![
+                text("Theme:").width(Length::Fixed(160.0)).size(12),
+                pick_list(
+                    &AppTheme::ALL[..],
+                    Some(config.theme),
+                    SettingsMessage::ThemeChanged
+                )
+                .text_size(12)
+                .padding(3)
+                .style(neutral_pick_list_style),
+            ]
+            .align_y(Alignment::Center),
+            row![
+                text("Event kind colors:").width(Length::Fixed(160.0)).size(12),
+                pick_list(
+                    &KindPalette::ALL[..],
+                    Some(config.kind_palette),
+                    SettingsMessage::KindPaletteChanged
+                )
+                .text_size(12)
+                .padding(3)
+                .style(neutral_pick_list_style),
+            ]
+            .align_y(Alignment::Center),
+            checkbox(config.restore_session)
+                .label("Reopen files from the last session on startup")
+                .size(14)
+                .text_size(12)
+                .on_toggle(SettingsMessage::RestoreSessionToggled),
+            checkbox(config.mini_timeline_utilization)
+                .label("Show busy threads in the mini timeline")
+                .size(14)
+                .text_size(12)
+                .on_toggle(SettingsMessage::MiniTimelineUtilizationToggled),
+            button(text("Clear recent files").size(12))
+                .style(crate::ui::neutral_button_style)
+                .padding(3)
+                .on_press(SettingsMessage::ClearRecentFiles),
+            text("Defaults for newly opened files").size(14),
+            row![
+                text("View:").width(Length::Fixed(160.0)).size(12),
+                pick_list(
+                    &ViewType::ALL[..],
+                    Some(config.default_view),
+                    SettingsMessage::DefaultViewChanged,
+                )
+                .text_size(12)
+                .padding(3)
+                .style(neutral_pick_list_style),
+            ]
+            .align_y(Alignment::Center),
+            row![
+                text("Color by:").width(Length::Fixed(160.0)).size(12),
+                pick_list(
+                    &ColorMode::ALL[..],
+                    Some(config.color_mode),
+                    SettingsMessage::ColorModeChanged,
+                )
+                .text_size(12)
+                .padding(3)
+                .style(neutral_pick_list_style),
+            ]
+            .align_y(Alignment::Center),
+            row![
+                text("Merge threads:").width(Length::Fixed(160.0)).size(12),
+                pick_list(
+                    &MergeStrategy::ALL[..],
+                    Some(config.merge_strategy),
+                    SettingsMessage::MergeStrategyChanged,
+                )
+                .text_size(12)
+                .padding(3)
+                .style(neutral_pick_list_style),
+            ]
+            .align_y(Alignment::Center),
+            checkbox(config.collapse_threads)
+                .label("Collapse all threads")
+                .size(14)
+                .text_size(12)
+                .on_toggle(SettingsMessage::CollapseThreadsToggled),
+            text(match crate::config::settings_path() {
+                Some(path) => format!("Saved to {}", path.display()),
+                None => "No config directory available; settings are not saved".to_string(),
+            })
+            .size(11),
+        ]
+        .spacing(6)
+        .padding(6);
+
+        let color_rules = column![
+            text("Color rules").size(16),
+            text(
+                "One rule per line, e.g. label ~ \"^LLVM\" => orange or \
+                 kind ~ \"Query\" => #4a90d9. The first matching rule wins over the color mode."
+            )
+            .size(12),
+            text_editor(&self.color_rules)
+                .placeholder("label ~ \"^LLVM\" => orange")
+                .on_action(SettingsMessage::ColorRulesEdited)
+                .font(iced::Font::MONOSPACE)
+                .size(12)
+                .height(Length::Fixed(140.0)),
+            row![
+                button(text("Apply").size(12))
+                    .style(crate::ui::neutral_button_style)
+                    .padding(3)
+                    .on_press(SettingsMessage::ApplyColorRules),
+                text(match &self.color_rules_error {
+                    Some(error) => error.clone(),
+                    None => match crate::color_rules::color_rules_path() {
+                        Some(path) => format!("Saved to {}", path.display()),
+                        None => "No config directory available; rules are not saved".to_string(),
+                    },
+                })
+                .size(11),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        ]
+        .spacing(6)
+        .padding(6);
+
         let hints = column![
             text("Hints").size(16),
             row![
@@ -47,11 +208,23 @@ impl SettingsPage {
                     .size(12),
                 text("Select a range to zoom the main view").size(12)
             ],
+            row![
+                text("Shift + left drag / header drag:")
+                    .width(Length::Fixed(160.0))
+                    .size(12),
+                text("Measure a time range and show where its time went").size(12)
+            ],
             row![
                 text("Mouse wheel:").width(Length::Fixed(160.0)).size(12),
                 text("Zoom horizontally centered on the cursor (hold Ctrl to scroll vertically)")
                     .size(12)
             ],
+            row![
+                text("Ctrl + Shift + mouse wheel:")
+                    .width(Length::Fixed(160.0))
+                    .size(12),
+                text("Change the lane height (vertical zoom)").size(12)
+            ],
             row![
                 text("Shift + mouse wheel:")
                     .width(Length::Fixed(160.0))
@@ -74,6 +247,24 @@ impl SettingsPage {
                 text("Esc:").width(Length::Fixed(160.0)).size(12),
                 text("Reset the current view (zoom/scroll)").size(12)
             ],
+            row![
+                text("W/S or +/-:").width(Length::Fixed(160.0)).size(12),
+                text("Zoom around the cursor (or the selected event)").size(12)
+            ],
+            row![
+                text("A/D or arrow keys:").width(Length::Fixed(160.0)).size(12),
+                text("Pan the timeline").size(12)
+            ],
+            row![
+                text("Arrow keys (with selection):")
+                    .width(Length::Fixed(160.0))
+                    .size(12),
+                text("Select parent, first child, previous or next sibling").size(12)
+            ],
+            row![
+                text("Alt + Left/Right:").width(Length::Fixed(160.0)).size(12),
+                text("Go back/forward through previous views (also mouse buttons 4/5)").size(12)
+            ],
             row![
                 text("Thread label click:")
                     .width(Length::Fixed(160.0))
@@ -90,6 +281,23 @@ impl SettingsPage {
                 text("Scrollbars:").width(Length::Fixed(160.0)).size(12),
                 text("Use scrollbars for precise horizontal/vertical navigation").size(12)
             ],
+            row![
+                text("Phase rules:").width(Length::Fixed(160.0)).size(12),
+                text(
+                    "Edit `phase_rules` in settings.toml; they apply to files opened afterwards"
+                )
+                .size(12)
+            ],
+            row![
+                text("Thread name rules:")
+                    .width(Length::Fixed(160.0))
+                    .size(12),
+                text(
+                    "Edit `thread_name_rules` in settings.toml; they apply to files opened \
+                     afterwards"
+                )
+                .size(12)
+            ],
         ]
         .spacing(6)
         .padding(6);
@@ -114,8 +322,10 @@ impl SettingsPage {
             ]
             .spacing(10)
             .align_y(Alignment::Center),
-            container(hints).padding(6).style(|_theme: &iced::Theme| {
-                container::Style::default().background(iced::Color::from_rgb(0.99, 0.99, 0.99))
+            container(preferences).padding(6),
+            container(color_rules).padding(6),
+            container(hints).padding(6).style(|theme: &iced::Theme| {
+                container::Style::default().background(crate::ui::shade(theme, 0.01))
             }),
         ]
         .spacing(8)
@@ -129,16 +339,7 @@ impl SettingsPage {
             .width(Length::Fill)
             .height(Length::Fill)
             .center_x(Length::Fill)
-            .style(|theme: &iced::Theme| {
-                let palette = theme.extended_palette();
-                container::Style::default()
-                    .background(palette.background.base.color)
-                    .border(iced::Border {
-                        color: palette.background.strong.color,
-                        width: 1.0,
-                        ..Default::default()
-                    })
-            })
+            .style(crate::ui::panel_style)
             .into()
     }
 }
@@ -0,0 +1,195 @@
+//! User-defined thread names.
+//!
+//! measureme only records numeric thread ids. Names come from two places:
+//! names given to a thread of one profile in the thread label panel, kept in
+//! `lineme/thread_names.toml` by profile path, and the `thread_name_rules` of
+//! the settings, which apply to every profile. Names given by hand win over
+//! rules.
+
+use crate::data::FileData;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const THREAD_NAMES_FILE_NAME: &str = "thread_names.toml";
+
+/// Which thread of a profile a rule names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThreadSelector {
+    /// The thread whose first event starts first.
+    EarliestStart,
+    /// The thread whose first event starts last.
+    LatestStart,
+    /// The thread with the most top-level busy time.
+    MostBusy,
+    MostEvents,
+    Id(u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThreadNameRule {
+    pub thread: ThreadSelector,
+    pub name: String,
+}
+
+impl ThreadNameRule {
+    /// rustc runs the driver on the thread that starts first.
+    pub fn defaults() -> Vec<ThreadNameRule> {
+        vec![ThreadNameRule {
+            thread: ThreadSelector::EarliestStart,
+            name: "main".to_string(),
+        }]
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct NamedThread {
+    thread_id: u32,
+    name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ProfileThreadNames {
+    path: PathBuf,
+    threads: Vec<NamedThread>,
+}
+
+/// Names given by hand, for every profile that has any.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThreadNameStore {
+    profiles: Vec<ProfileThreadNames>,
+}
+
+/// Path of the thread names file, if the platform has a config directory.
+pub fn thread_names_path() -> Option<PathBuf> {
+    crate::config::config_dir().map(|dir| dir.join(THREAD_NAMES_FILE_NAME))
+}
+
+impl ThreadNameStore {
+    /// Load the thread names file. A missing file yields no names; one that
+    /// cannot be parsed is moved aside, so saving does not lose the names.
+    pub fn load() -> Result<ThreadNameStore, String> {
+        let Some(path) = thread_names_path() else {
+            return Ok(ThreadNameStore::default());
+        };
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(ThreadNameStore::default());
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                return Err(crate::config::parse_error(&path, e));
+            }
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        toml::from_str(&contents).map_err(|e| crate::config::parse_error(&path, e))
+    }
+
+    /// Write the thread names file, creating the config directory if needed.
+    pub fn save(&self) -> Result<(), String> {
+        let path = thread_names_path().ok_or_else(|| "No config directory available".to_string())?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let contents = toml::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize thread names: {}", e))?;
+        std::fs::write(&path, contents)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Names given by hand to the threads of the profile at `path`.
+    pub fn names(&self, path: &Path) -> HashMap<u32, String> {
+        self.profiles
+            .iter()
+            .find(|profile| profile.path == path)
+            .map(|profile| {
+                profile
+                    .threads
+                    .iter()
+                    .map(|thread| (thread.thread_id, thread.name.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Name a thread of the profile at `path`. An empty name removes it, so
+    /// the rules apply again.
+    pub fn set_name(&mut self, path: &Path, thread_id: u32, name: &str) {
+        let index = match self.profiles.iter().position(|profile| profile.path == path) {
+            Some(index) => index,
+            None => {
+                self.profiles.push(ProfileThreadNames {
+                    path: path.to_path_buf(),
+                    threads: Vec::new(),
+                });
+                self.profiles.len() - 1
+            }
+        };
+        let threads = &mut self.profiles[index].threads;
+        threads.retain(|thread| thread.thread_id != thread_id);
+        let name = name.trim();
+        if !name.is_empty() {
+            threads.push(NamedThread {
+                thread_id,
+                name: name.to_string(),
+            });
+        }
+        if threads.is_empty() {
+            self.profiles.remove(index);
+        }
+    }
+}
+
+fn select_thread(selector: ThreadSelector, data: &FileData) -> Option<u32> {
+    let threads = data
+        .timeline
+        .thread_groups
+        .iter()
+        .flat_map(|group| group.threads.iter());
+    let start_ns = |thread: &crate::data::ThreadData| {
+        thread
+            .thread_root
+            .map_or(u64::MAX, |root| data.events[root.index()].start_ns)
+    };
+    let summary = |thread_id: u32| data.thread_summaries.get(&thread_id);
+    // Ties go to the lowest thread id.
+    let by_key = |key: &dyn Fn(&crate::data::ThreadData) -> i128| {
+        threads
+            .clone()
+            .min_by_key(|thread| (key(thread), thread.thread_id))
+            .map(|thread| thread.thread_id)
+    };
+    match selector {
+        ThreadSelector::EarliestStart => by_key(&|thread| start_ns(thread) as i128),
+        ThreadSelector::LatestStart => by_key(&|thread| -(start_ns(thread) as i128)),
+        ThreadSelector::MostBusy => by_key(&|thread| {
+            -(summary(thread.thread_id).map_or(0, |summary| summary.busy_ns) as i128)
+        }),
+        ThreadSelector::MostEvents => by_key(&|thread| {
+            -(summary(thread.thread_id).map_or(0, |summary| summary.event_count) as i128)
+        }),
+        ThreadSelector::Id(id) => threads
+            .clone()
+            .any(|thread| thread.thread_id == id)
+            .then_some(id),
+    }
+}
+
+/// Names of the threads of one profile: the first matching rule for each
+/// thread, replaced by the names given by hand.
+pub fn resolve(
+    rules: &[ThreadNameRule],
+    manual: HashMap<u32, String>,
+    data: &FileData,
+) -> HashMap<u32, String> {
+    let mut names = HashMap::new();
+    for rule in rules {
+        if let Some(thread_id) = select_thread(rule.thread, data) {
+            names.entry(thread_id).or_insert_with(|| rule.name.clone());
+        }
+    }
+    names.extend(manual);
+    names
+}
@@ -1,10 +1,15 @@
+mod colors;
 mod events;
 mod header;
+mod histogram;
 mod mini_timeline;
+mod phases;
 mod threads;
 mod ticks;
+mod utilization;
 
 use crate::Message;
+use crate::analysis::thread_summary::ThreadSummary;
 use crate::data::{EventId, ThreadGroup, ThreadGroupMipMapShadows, TimelineData, TimelineEvent};
 // Removed unused imports: kinds lookup uses are performed via passed-in `kinds`
 // and `symbols` variables; avoid importing Symbol/HashMap here.
@@ -16,25 +21,47 @@ pub use crate::data::ColorMode;
 use crate::scrollbar;
 use events::EventsProgram;
 use header::HeaderProgram;
+use histogram::{HISTOGRAM_HEIGHT, HISTOGRAM_WIDTH, HistogramProgram};
 use iced::advanced::widget::{self, Tree, Widget};
 use iced::advanced::{Clipboard, Layout, Shell, layout, renderer};
 use iced::mouse;
 use iced::widget::canvas::Canvas;
-use iced::widget::{Space, button, column, container, row, text};
+use iced::widget::{Space, button, column, container, row, scrollable, text, text_input};
 use iced::{Color, Element, Event, Length, Point, Rectangle, Size, Theme};
 use mini_timeline::MiniTimelineProgram;
+use phases::{PHASE_LANE_HEIGHT, PhasesProgram};
 use threads::ThreadsProgram;
+use utilization::UtilizationProgram;
 
+/// Default width of the thread label column; dragging its right edge
+/// resizes it between `MIN_LABEL_WIDTH` and `MAX_LABEL_WIDTH`.
 pub const LABEL_WIDTH: f64 = 150.0_f64;
+pub const MIN_LABEL_WIDTH: f64 = 80.0_f64;
+pub const MAX_LABEL_WIDTH: f64 = 480.0_f64;
 pub const HEADER_HEIGHT: f64 = 55.0_f64;
+/// Widget id of the thread name input, focused when renaming starts.
+pub const THREAD_NAME_INPUT: &str = "thread-name-input";
 pub const MINI_TIMELINE_HEIGHT: f64 = 40.0_f64;
+pub const UTILIZATION_HEIGHT: f64 = 32.0_f64;
+/// Default height of one depth lane; vertical zoom scales it between
+/// `MIN_LANE_HEIGHT` and `MAX_LANE_HEIGHT`.
 pub const LANE_HEIGHT: f64 = 20.0_f64;
+pub const MIN_LANE_HEIGHT: f64 = 4.0_f64;
+pub const MAX_LANE_HEIGHT: f64 = 48.0_f64;
+/// Lanes thinner than this are drawn without labels.
+pub const MIN_TEXT_LANE_HEIGHT: f64 = 14.0_f64;
 pub const LANE_SPACING: f64 = 5.0_f64;
+/// The pinned area scrolls on its own beyond this height.
+pub const MAX_PINNED_HEIGHT: f64 = 320.0_f64;
 pub const DRAG_THRESHOLD: f64 = 3.0_f64;
 pub const EVENT_LEFT_PADDING: f64 = 2.0_f64;
 pub const SCROLLBAR_THICKNESS: f32 = 18.0;
 pub const SCROLLBAR_CORNER_GAP: f32 = 6.0;
 
+/// Shading and edges of the range picked with the measurement tool.
+const MEASURE_FILL_COLOR: Color = Color::from_rgba(0.1, 0.55, 0.3, 0.15);
+const MEASURE_EDGE_COLOR: Color = Color::from_rgb(0.1, 0.45, 0.25);
+
 // `UnalignedU64` moved to `src/data.rs` so that the profiling data types
 // can be defined without depending on the timeline module.
 
@@ -62,10 +89,10 @@ pub fn color_from_label(label: &str) -> Color {
 
 // Convert HSL to RGB Color. Hue is in degrees [0,360), s and l are [0,1].
 // color_from_hsl is provided by `data.rs` and re-exported above.
-pub fn total_timeline_height(thread_groups: &[ThreadGroup]) -> f64 {
+pub fn total_timeline_height(thread_groups: &[ThreadGroup], lane_height: f64) -> f64 {
     let mut total_height = 0.0_f64;
     for group in thread_groups {
-        let lane_total_height = group_total_height(group);
+        let lane_total_height = group_total_height(group, lane_height);
         total_height += lane_total_height + LANE_SPACING;
     }
     total_height
@@ -108,11 +135,11 @@ pub fn viewport_ns_range(
 
 /// Return the total vertical height occupied by a thread group (all lanes),
 /// respecting collapsed state.
-pub fn group_total_height(group: &ThreadGroup) -> f64 {
+pub fn group_total_height(group: &ThreadGroup, lane_height: f64) -> f64 {
     if group.is_collapsed {
-        LANE_HEIGHT
+        lane_height
     } else {
-        (group.max_depth + 1) as f64 * LANE_HEIGHT
+        (group.max_depth + 1) as f64 * lane_height
     }
 }
 
@@ -164,15 +191,48 @@ pub fn format_duration(ns: u64) -> String {
 pub struct TimelineViewArgs<'a> {
     pub timeline_data: &'a TimelineData,
     pub events: &'a [TimelineEvent],
+    /// Groups in the vertically scrolled area.
     pub thread_groups: &'a [ThreadGroup],
+    /// Groups kept above the scrolled area.
+    pub pinned_thread_groups: &'a [ThreadGroup],
     pub kinds: &'a [crate::data::KindInfo],
     pub zoom_level: f64,
     pub selected_event: &'a Option<EventId>,
+    pub label_instances: &'a [EventId],
+    pub label_histogram: &'a crate::analysis::histogram::DurationHistogram,
+    /// Histogram bucket picked in the details panel and its instances, which
+    /// are highlighted instead of all `label_instances`.
+    pub histogram_bucket: Option<u32>,
+    pub bucket_instances: &'a [EventId],
+    pub measured_range: Option<&'a crate::analysis::range::RangeStats>,
+    /// Idle gaps to shade; empty when shading is off.
+    pub idle_gaps: &'a [crate::analysis::idle_gaps::IdleGap],
+    /// Outliers to outline; empty when outlines are off.
+    pub outliers: &'a [crate::analysis::outliers::Outlier],
+    pub query_blocking: &'a crate::analysis::blocking::QueryBlocking,
+    pub show_all_query_links: bool,
+    pub phases: &'a crate::analysis::phases::Phases,
+    pub utilization: &'a crate::analysis::utilization::Utilization,
+    pub density: &'a crate::analysis::density::ActivityDensity,
+    /// Also draw the busy thread curve inside the mini timeline.
+    pub mini_timeline_utilization: bool,
     pub scroll_offset_x: f64,
     pub scroll_offset_y: f64,
     pub viewport_width: f64,
     pub viewport_height: f64,
+    pub lane_height: f64,
+    pub label_width: f64,
+    /// Per-thread totals shown in the thread labels.
+    pub thread_summaries: &'a std::collections::HashMap<u32, ThreadSummary>,
+    pub thread_names: &'a std::collections::HashMap<u32, String>,
+    /// Groups can be merged and split by hand in the label panel.
+    pub manual_merge: bool,
+    /// The thread being renamed and the name typed so far.
+    pub renaming_thread: Option<&'a (u32, String)>,
     pub color_mode: ColorMode,
+    pub rule_colors: &'a crate::color_rules::RuleColors,
+    /// Self and parent times for the color modes that need them.
+    pub event_metrics: Option<&'a crate::analysis::metrics::EventMetrics>,
     pub symbols: &'a crate::symbols::Symbols,
 }
 
@@ -181,17 +241,45 @@ pub fn view<'a>(args: TimelineViewArgs<'a>) -> Element<'a, Message> {
         timeline_data,
         events,
         thread_groups,
+        pinned_thread_groups,
         zoom_level,
         selected_event,
+        label_instances,
+        label_histogram,
+        histogram_bucket,
+        bucket_instances,
+        measured_range,
+        idle_gaps,
+        outliers,
+        query_blocking,
+        show_all_query_links,
+        phases,
+        utilization,
+        density,
+        mini_timeline_utilization,
         scroll_offset_x,
         scroll_offset_y,
         viewport_width,
         viewport_height,
+        lane_height,
+        label_width,
+        thread_summaries,
+        thread_names,
+        manual_merge,
+        renaming_thread,
         color_mode,
+        rule_colors,
+        event_metrics,
         symbols,
         kinds,
     } = args;
-    if events.is_empty() || thread_groups.is_empty() {
+    let measured_span = measured_range.map(|range| (range.start_ns, range.end_ns));
+    let highlighted_instances = if histogram_bucket.is_some() {
+        bucket_instances
+    } else {
+        label_instances
+    };
+    if events.is_empty() || (thread_groups.is_empty() && pinned_thread_groups.is_empty()) {
         return container(text("No events to display"))
             .width(Length::Fill)
             .height(Length::Fill)
@@ -207,10 +295,15 @@ pub fn view<'a>(args: TimelineViewArgs<'a>) -> Element<'a, Message> {
         .saturating_sub(timeline_data.min_ns)
         .max(1);
 
-    let total_height = total_timeline_height(thread_groups) as f32;
+    let total_height = total_timeline_height(thread_groups, lane_height) as f32;
 
     let scroll_offset_x_px = scroll_offset_x * zoom_level;
     let mini_timeline_canvas = Canvas::new(MiniTimelineProgram {
+        events,
+        label_instances: highlighted_instances,
+        utilization: mini_timeline_utilization.then_some(utilization),
+        density,
+        selected_event: *selected_event,
         min_ns: timeline_data.min_ns,
         max_ns: timeline_data.max_ns,
         zoom_level,
@@ -225,35 +318,135 @@ pub fn view<'a>(args: TimelineViewArgs<'a>) -> Element<'a, Message> {
         max_ns: timeline_data.max_ns,
         zoom_level,
         scroll_offset_x: scroll_offset_x_px,
+        measured_range: measured_span,
     })
     .width(Length::Fill)
     .height(Length::Fixed(HEADER_HEIGHT as f32));
 
-    let threads_canvas = Canvas::new(ThreadsProgram {
-        thread_groups,
-        scroll_offset_y,
-    })
-    .width(Length::Fixed(LABEL_WIDTH as f32))
-    .height(Length::Fill);
+    // The phase track is only shown when some label matched a phase rule.
+    let phase_track: Element<'a, Message> = if phases.lanes > 0 {
+        let height = (phases.lanes as f64 * PHASE_LANE_HEIGHT) as f32;
+        row![
+            container(text("Phases").size(11))
+                .width(Length::Fixed(label_width as f32))
+                .height(Length::Fill)
+                .padding([1, 6]),
+            Canvas::new(PhasesProgram {
+                phases,
+                min_ns: timeline_data.min_ns,
+                zoom_level,
+                scroll_offset_x,
+            })
+            .width(Length::Fill)
+            .height(Length::Fill)
+        ]
+        .height(Length::Fixed(height))
+        .into()
+    } else {
+        Space::new().height(Length::Fixed(0.0)).into()
+    };
 
-    let events_canvas = Canvas::new(EventsProgram {
-        events,
-        thread_groups,
+    let utilization_canvas = Canvas::new(UtilizationProgram {
+        utilization,
         min_ns: timeline_data.min_ns,
-        max_ns: timeline_data.max_ns,
         zoom_level,
-        selected_event: *selected_event,
         scroll_offset_x,
+    })
+    .width(Length::Fill)
+    .height(Length::Fixed(UTILIZATION_HEIGHT as f32));
+
+    // Average parallelism over the visible time range.
+    let visible_start_ns = timeline_data.min_ns as f64 + scroll_offset_x.max(0.0);
+    let average_busy = utilization.average(
+        visible_start_ns,
+        visible_start_ns + viewport_width / zoom_level.max(1e-9),
+    );
+
+    let threads_program = |thread_groups, scroll_offset_y| ThreadsProgram {
+        events,
+        thread_groups,
+        summaries: thread_summaries,
+        thread_names,
+        manual_merge,
+        scroll_offset_y,
+        lane_height,
+    };
+    let events_program =
+        |thread_groups, scroll_offset_y, viewport_height, handles_keys| EventsProgram {
+            events,
+            thread_groups,
+            min_ns: timeline_data.min_ns,
+            max_ns: timeline_data.max_ns,
+            zoom_level,
+            selected_event: *selected_event,
+            label_instances: highlighted_instances,
+            measured_range: measured_span,
+            idle_gaps,
+            outliers,
+            query_blocking,
+            show_all_query_links,
+            scroll_offset_x,
+            scroll_offset_y,
+            viewport_width,
+            viewport_height,
+            lane_height,
+            color_mode,
+            rule_colors,
+            event_metrics,
+            symbols,
+            kinds,
+            handles_keys,
+        };
+
+    let threads_canvas = Canvas::new(threads_program(thread_groups, scroll_offset_y))
+        .width(Length::Fixed(label_width as f32))
+        .height(Length::Fill);
+    // Keys typed into the thread name editor must not move the timeline.
+    let events_canvas = Canvas::new(events_program(
+        thread_groups,
         scroll_offset_y,
-        viewport_width,
         viewport_height,
-        color_mode,
-        symbols,
-        kinds,
-    })
+        renaming_thread.is_none(),
+    ))
     .width(Length::Fill)
     .height(Length::Fill);
 
+    // Pinned groups get their own canvases above the main area, which scroll
+    // on their own once they outgrow it.
+    let pinned_track: Element<'a, Message> = if pinned_thread_groups.is_empty() {
+        Space::new().height(Length::Fixed(0.0)).into()
+    } else {
+        let content_height = total_timeline_height(pinned_thread_groups, lane_height);
+        let height = content_height.min(MAX_PINNED_HEIGHT);
+        let pinned_rows = row![
+            Canvas::new(threads_program(pinned_thread_groups, 0.0))
+                .width(Length::Fixed(label_width as f32))
+                .height(Length::Fixed(content_height as f32)),
+            // The main canvas handles the keyboard for both.
+            Canvas::new(events_program(pinned_thread_groups, 0.0, content_height, false))
+                .width(Length::Fill)
+                .height(Length::Fixed(content_height as f32)),
+        ];
+        container(
+            scrollable::Scrollable::new(pinned_rows).direction(scrollable::Direction::Vertical(
+                scrollable::Scrollbar::new()
+                    .width(SCROLLBAR_THICKNESS)
+                    .scroller_width(SCROLLBAR_THICKNESS)
+                    .spacing(0.0),
+            )),
+        )
+        .height(Length::Fixed(height as f32))
+        .style(|theme: &Theme| container::Style {
+            border: iced::Border {
+                color: crate::ui::shade(theme, 0.3),
+                width: 1.0,
+                radius: 0.0.into(),
+            },
+            ..Default::default()
+        })
+        .into()
+    };
+
     let events_view = container(ViewportCatcher::new(
         WheelCatcher::new(events_canvas),
         |size| Message::TimelineViewportChanged {
@@ -330,6 +523,46 @@ pub fn view<'a>(args: TimelineViewArgs<'a>) -> Element<'a, Message> {
     .height(Length::Fill)
     .width(Length::Fill);
 
+    // Left area above the thread labels: collapse/expand all buttons, or the
+    // thread name editor while a thread is renamed.
+    let header_left: Element<'a, Message> = match renaming_thread {
+        Some((thread_id, name)) => thread_name_editor(*thread_id, name),
+        None => row![
+            // Collapse button
+            button(
+                container(
+                    row![text("-").size(18), text("Collapse").size(12)]
+                        .spacing(4)
+                        .align_y(iced::Alignment::Center),
+                )
+                .center_x(Length::Fill)
+                .center_y(Length::Fill),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(0)
+            .style(crate::ui::neutral_button_style)
+            .on_press(Message::CollapseAllThreads),
+            // Expand button
+            button(
+                container(
+                    row![text("+").size(18), text("Expand").size(12)]
+                        .spacing(4)
+                        .align_y(iced::Alignment::Center),
+                )
+                .center_x(Length::Fill)
+                .center_y(Length::Fill),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(0)
+            .style(crate::ui::neutral_button_style)
+            .on_press(Message::ExpandAllThreads),
+        ]
+        .height(Length::Fill)
+        .into(),
+    };
+
     // Mini timeline should span the full window width (including the label area).
     let main_view = column![
         // Full-width mini timeline on its own row.
@@ -338,44 +571,28 @@ pub fn view<'a>(args: TimelineViewArgs<'a>) -> Element<'a, Message> {
             column![
                 // Header remains aligned with the events area (leaving space for labels).
                 row![
-                    // Left area above the thread labels: collapse/expand all buttons
-                    row![
-                        // Collapse button
-                        button(
-                            container(
-                                row![text("-").size(18), text("Collapse").size(12)]
-                                    .spacing(4)
-                                    .align_y(iced::Alignment::Center),
-                            )
-                            .center_x(Length::Fill)
-                            .center_y(Length::Fill),
-                        )
-                        .width(Length::Fill)
-                        .height(Length::Fill)
-                        .padding(0)
-                        .style(crate::ui::neutral_button_style)
-                        .on_press(Message::CollapseAllThreads),
-                        // Expand button
-                        button(
-                            container(
-                                row![text("+").size(18), text("Expand").size(12)]
-                                    .spacing(4)
-                                    .align_y(iced::Alignment::Center),
-                            )
-                            .center_x(Length::Fill)
-                            .center_y(Length::Fill),
-                        )
-                        .width(Length::Fill)
-                        .height(Length::Fill)
-                        .padding(0)
-                        .style(crate::ui::neutral_button_style)
-                        .on_press(Message::ExpandAllThreads),
-                    ]
-                    .width(Length::Fixed(LABEL_WIDTH as f32))
-                    .height(Length::Fill),
+                    container(header_left)
+                        .width(Length::Fixed(label_width as f32))
+                        .height(Length::Fill),
                     header_canvas
                 ]
                 .height(Length::Fixed(HEADER_HEIGHT as f32)),
+                phase_track,
+                row![
+                    container(
+                        column![
+                            text("Busy threads").size(11),
+                            text(format!("avg {:.2}", average_busy)).size(11),
+                        ]
+                        .spacing(0),
+                    )
+                    .width(Length::Fixed(label_width as f32))
+                    .height(Length::Fill)
+                    .padding([1, 6]),
+                    utilization_canvas
+                ]
+                .height(Length::Fixed(UTILIZATION_HEIGHT as f32)),
+                pinned_track,
                 row![threads_canvas, events_column].height(Length::Fill)
             ]
             .height(Length::Fill),
@@ -393,7 +610,7 @@ pub fn view<'a>(args: TimelineViewArgs<'a>) -> Element<'a, Message> {
     // returning a different root widget that wraps it (when the details panel is
     // shown), iced will rebuild the subtree and the canvas state gets reset.
     //
-    // By always returning the same root `column![main_view, details_panel]`, the
+    // By always returning the same root `column![main_view, range_panel, details_panel]`, the
     // canvas state persists and the first double-click works as expected.
     let details_panel: Element<'a, Message> = if let Some(event) = display_event {
         // Build details column. Thread-root events are shown more compactly:
@@ -402,11 +619,8 @@ pub fn view<'a>(args: TimelineViewArgs<'a>) -> Element<'a, Message> {
         let mut details_col = column![];
 
         if event.is_thread_root {
-            // Thread-root events: compact view showing only the thread id.
-            details_col = details_col.push(row![
-                text("Thread:").width(Length::Fixed(80.0)).size(12),
-                text(format!("{}", event.thread_id)).size(12)
-            ]);
+            // Thread-root events: compact view showing only the thread.
+            details_col = details_col.push(thread_details_row(event.thread_id, thread_names));
         } else {
             details_col = details_col
                 .push(row![
@@ -428,10 +642,7 @@ pub fn view<'a>(args: TimelineViewArgs<'a>) -> Element<'a, Message> {
                     )
                     .size(12)
                 ])
-                .push(row![
-                    text("Thread:").width(Length::Fixed(80.0)).size(12),
-                    text(format!("{}", event.thread_id)).size(12)
-                ])
+                .push(thread_details_row(event.thread_id, thread_names))
                 .push(row![
                     text("Start:").width(Length::Fixed(80.0)).size(12),
                     text(format_duration(
@@ -451,7 +662,73 @@ pub fn view<'a>(args: TimelineViewArgs<'a>) -> Element<'a, Message> {
                 .push(row![
                     text("Duration:").width(Length::Fixed(80.0)).size(12),
                     text(format_duration(event.duration_ns)).size(12)
+                ])
+                .push(row![
+                    text("Instances:").width(Length::Fixed(80.0)).size(12),
+                    text(format!("{} with this label", label_instances.len())).size(12)
+                ]);
+
+            for query in selected_event
+                .iter()
+                .flat_map(|&id| query_blocking.links_of(id))
+            {
+                let (title, other) = if Some(query.blocked) == *selected_event {
+                    ("Blocked on:", query.executing)
+                } else {
+                    ("Blocking:", Some(query.blocked))
+                };
+                let Some(other) = other else {
+                    details_col = details_col.push(row![
+                        text(title).width(Length::Fixed(80.0)).size(12),
+                        text("no matching execution on another thread").size(12)
+                    ]);
+                    continue;
+                };
+                let other_event = &events[other.index()];
+                details_col = details_col.push(
+                    row![
+                        text(title).width(Length::Fixed(80.0)).size(12),
+                        text(format!(
+                            "{} ({} overlap)",
+                            crate::data::thread_name(thread_names, other_event.thread_id),
+                            format_duration(crate::analysis::blocking::overlap_ns(
+                                event,
+                                other_event
+                            ))
+                        ))
+                        .size(12),
+                        button(text("Select").size(12))
+                            .style(crate::ui::toolbar_button_style)
+                            .padding(2)
+                            .on_press(Message::EventSelected(other)),
+                    ]
+                    .spacing(8)
+                    .align_y(iced::Alignment::Center),
+                );
+            }
+
+            if !label_histogram.counts.is_empty() {
+                details_col = details_col.push(row![
+                    text("Durations:").width(Length::Fixed(80.0)).size(12),
+                    Canvas::new(HistogramProgram {
+                        histogram: label_histogram,
+                        current_bucket: crate::data::duration_bucket(event.duration_ns),
+                        selected_bucket: histogram_bucket,
+                    })
+                    .width(Length::Fixed(HISTOGRAM_WIDTH))
+                    .height(Length::Fixed(HISTOGRAM_HEIGHT)),
                 ]);
+            }
+
+            if let Some(bucket) = histogram_bucket {
+                details_col = details_col.push(bucket_instances_list(
+                    bucket,
+                    bucket_instances,
+                    events,
+                    timeline_data.min_ns,
+                    thread_names,
+                ));
+            }
 
             // Ensure additional fields are shown below for non-thread-root events.
         }
@@ -508,11 +785,123 @@ pub fn view<'a>(args: TimelineViewArgs<'a>) -> Element<'a, Message> {
             .into()
     };
 
-    column![main_view, details_panel]
+    let range_panel: Element<'a, Message> = match measured_range {
+        Some(range) => crate::analysis::range::view(
+            range,
+            timeline_data.min_ns,
+            symbols,
+            kinds,
+            thread_names,
+        ),
+        None => Space::new()
+            .width(Length::Fill)
+            .height(Length::Fixed(0.0))
+            .into(),
+    };
+
+    column![main_view, range_panel, details_panel]
         .height(Length::Fill)
         .into()
 }
 
+// The thread of the selected event, with a button to rename it.
+fn thread_details_row<'a>(
+    thread_id: u32,
+    thread_names: &std::collections::HashMap<u32, String>,
+) -> Element<'a, Message> {
+    let thread = match thread_names.get(&thread_id) {
+        Some(name) => format!("{} ({})", name, thread_id),
+        None => format!("{}", thread_id),
+    };
+    row![
+        text("Thread:").width(Length::Fixed(80.0)).size(12),
+        text(thread).size(12),
+        button(text("Rename").size(11))
+            .style(crate::ui::toolbar_button_style)
+            .padding([0, 4])
+            .on_press(Message::RenameThread(thread_id)),
+    ]
+    .spacing(6)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+// Replaces the collapse and expand buttons while a thread is renamed.
+fn thread_name_editor<'a>(thread_id: u32, name: &str) -> Element<'a, Message> {
+    column![
+        row![
+            text(format!("Name of thread {}", thread_id)).size(11),
+            Space::new().width(Length::Fill),
+            button(text("Cancel").size(11))
+                .style(crate::ui::toolbar_button_style)
+                .padding([0, 4])
+                .on_press(Message::RenameThreadCancelled),
+        ]
+        .align_y(iced::Alignment::Center),
+        text_input("Empty to reset", name)
+            .id(THREAD_NAME_INPUT)
+            .on_input(Message::ThreadNameEdited)
+            .on_submit(Message::ThreadNameSubmitted)
+            .size(12)
+            .padding(3),
+    ]
+    .spacing(4)
+    .padding([4, 6])
+    .into()
+}
+
+/// Longest list of instances shown for a histogram bucket.
+const MAX_BUCKET_ROWS: usize = 100;
+
+fn bucket_instances_list<'a>(
+    bucket: u32,
+    instances: &[EventId],
+    events: &[TimelineEvent],
+    min_ns: u64,
+    thread_names: &std::collections::HashMap<u32, String>,
+) -> Element<'a, Message> {
+    let (start_ns, end_ns) = crate::analysis::histogram::DurationHistogram::bucket_range_ns(bucket);
+    let mut rows = column![].spacing(2);
+    for &id in instances.iter().take(MAX_BUCKET_ROWS) {
+        let event = &events[id.index()];
+        let start = event.start_ns.saturating_sub(min_ns);
+        rows = rows.push(
+            row![
+                text(format_duration(start)).size(12).width(Length::Fixed(80.0)),
+                text(format_duration(event.duration_ns))
+                    .size(12)
+                    .width(Length::Fixed(80.0)),
+                text(crate::data::thread_name(thread_names, event.thread_id))
+                    .size(12)
+                    .width(Length::Fixed(80.0)),
+                crate::ui::show_range_button(
+                    start as f64,
+                    (start + event.duration_ns) as f64,
+                    Some(id),
+                ),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center),
+        );
+    }
+    if instances.len() > MAX_BUCKET_ROWS {
+        rows = rows.push(text(format!("… {} more", instances.len() - MAX_BUCKET_ROWS)).size(12));
+    }
+
+    column![
+        text(format!(
+            "{} instances between {} and {} (start, duration):",
+            instances.len(),
+            format_duration(start_ns),
+            format_duration(end_ns)
+        ))
+        .size(12),
+        scrollable(rows).height(Length::Fixed(100.0)),
+    ]
+    .spacing(4)
+    .into()
+}
+
 fn group_contains_thread(group: &ThreadGroup, thread_id: u32) -> bool {
     group
         .threads
@@ -520,6 +909,35 @@ fn group_contains_thread(group: &ThreadGroup, thread_id: u32) -> bool {
         .any(|thread| thread.thread_id == thread_id)
 }
 
+/// Index of the group containing `thread_id`.
+pub fn group_index_of_thread(thread_groups: &[ThreadGroup], thread_id: u32) -> Option<usize> {
+    thread_groups
+        .iter()
+        .position(|group| group_contains_thread(group, thread_id))
+}
+
+/// Top of the lane `event` is drawn in, in content coordinates (before
+/// vertical scrolling). Events hidden in a collapsed group map to its top lane.
+pub fn event_lane_y(
+    thread_groups: &[ThreadGroup],
+    event: &TimelineEvent,
+    lane_height: f64,
+) -> Option<f64> {
+    let mut y_offset = 0.0_f64;
+    for group in thread_groups {
+        if group_contains_thread(group, event.thread_id) {
+            let depth = if group.is_collapsed {
+                0
+            } else {
+                crate::data::display_depth(group.show_thread_roots, event)
+            };
+            return Some(y_offset + depth as f64 * lane_height);
+        }
+        y_offset += group_total_height(group, lane_height) + LANE_SPACING;
+    }
+    None
+}
+
 // display_depth is provided by `data.rs` and re-exported via `crate::data`.
 
 pub struct WheelCatcher<'a, Message, Theme, Renderer> {
@@ -0,0 +1,91 @@
+// Event fill colors for each `ColorMode`.
+use super::color_from_label;
+use crate::analysis::metrics::EventMetrics;
+use crate::color_rules::RuleColors;
+use crate::data::{ColorMode, EventId, KindInfo, TimelineEvent, color_from_hsl};
+use crate::symbols::Symbols;
+use iced::Color;
+
+/// Color for events a mode has no value for, e.g. events without
+/// additional data when coloring by it.
+const NEUTRAL_COLOR: Color = Color::from_rgb(0.8, 0.8, 0.8);
+
+/// Dark or light label text, whichever reads better on `fill`.
+pub(crate) fn contrast_text_color(fill: Color) -> Color {
+    // Approximate brightness using the sRGB luminance weights.
+    let luminance = 0.2126 * fill.r + 0.7152 * fill.g + 0.0722 * fill.b;
+    if luminance > 0.55 {
+        Color::from_rgb(0.12, 0.12, 0.12)
+    } else {
+        Color::from_rgb(0.96, 0.96, 0.96)
+    }
+}
+
+/// Light yellow through orange to red, for `t` in `[0, 1]`. Kept light enough
+/// for dark label text.
+pub(crate) fn heat_color(t: f32) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    color_from_hsl(55.0 - 55.0 * t, 0.85, 0.82 - 0.22 * t)
+}
+
+/// Position of `ns` on a log scale from 1ns to `max_ns`.
+fn log_fraction(ns: u64, max_ns: f64) -> f32 {
+    ((ns.max(1) as f64).ln() / max_ns.max(2.0).ln()) as f32
+}
+
+/// Picks the fill color of events for one draw.
+pub(crate) struct EventColors<'a> {
+    pub(crate) mode: ColorMode,
+    /// User rules, which take precedence over the mode.
+    pub(crate) rules: &'a RuleColors,
+    pub(crate) kinds: &'a [KindInfo],
+    pub(crate) symbols: &'a Symbols,
+    /// Self and parent times; the modes that need them fall back to the
+    /// neutral color until they are computed.
+    pub(crate) metrics: Option<&'a EventMetrics>,
+    /// Length of the visible time range, the top of the heat scales.
+    pub(crate) visible_ns: f64,
+}
+
+impl EventColors<'_> {
+    pub(crate) fn color(&self, id: EventId, event: &TimelineEvent) -> Color {
+        if let Some(color) = self.rules.color(event) {
+            return color;
+        }
+        let label = || self.symbols.resolve(event.label);
+        match self.mode {
+            ColorMode::Kind => self
+                .kinds
+                .get(event.kind_index as usize)
+                .map(|kind| kind.color)
+                .unwrap_or_else(|| color_from_label(label())),
+            ColorMode::Event => color_from_label(label()),
+            ColorMode::Duration => heat_color(log_fraction(event.duration_ns, self.visible_ns)),
+            ColorMode::SelfTime => match self.metrics {
+                Some(metrics) => {
+                    heat_color(log_fraction(metrics.self_ns[id.index()], self.visible_ns))
+                }
+                None => NEUTRAL_COLOR,
+            },
+            ColorMode::Thread => {
+                // Golden ratio steps keep neighbouring thread ids apart.
+                let hue = (event.thread_id as f32 * 137.508) % 360.0;
+                color_from_hsl(hue, 0.55, 0.75)
+            }
+            ColorMode::FirstData => match event
+                .additional_data
+                .as_deref()
+                .and_then(|data| data.first())
+            {
+                Some(&data) => color_from_label(self.symbols.resolve(data)),
+                None => NEUTRAL_COLOR,
+            },
+            ColorMode::PercentOfParent => match self.metrics {
+                Some(metrics) if metrics.parent_ns[id.index()] > 0 => {
+                    heat_color(event.duration_ns as f32 / metrics.parent_ns[id.index()] as f32)
+                }
+                _ => NEUTRAL_COLOR,
+            },
+        }
+    }
+}
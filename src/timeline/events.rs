@@ -3,15 +3,27 @@ use iced::mouse;
 use iced::widget::canvas::{self, Geometry, Program};
 use iced::{Color, Point, Rectangle, Renderer, Size, Theme, Vector, keyboard};
 
-/// Fixed light color used for thread-root events in the timeline.
-const THREAD_ROOT_COLOR: Color = Color::from_rgb(0.87, 0.87, 0.87);
 
-use super::{EVENT_LEFT_PADDING, LANE_HEIGHT};
+/// Outline for other instances of the selected event's label.
+const LABEL_INSTANCE_COLOR: Color = Color::from_rgb(0.95, 0.55, 0.1);
+
+/// Outline for instances far longer than usual for their label.
+const OUTLIER_COLOR: Color = Color::from_rgb(0.85, 0.1, 0.45);
+
+/// Arrows from blocked queries to the execution they wait on.
+const QUERY_LINK_COLOR: Color = Color::from_rgb(0.75, 0.15, 0.15);
+
+/// Fill for idle gaps between top-level events.
+const IDLE_GAP_COLOR: Color = Color::from_rgba(0.85, 0.25, 0.2, 0.12);
+
+use super::colors::{EventColors, contrast_text_color};
+use super::{EVENT_LEFT_PADDING, MIN_TEXT_LANE_HEIGHT};
 use super::{
-    EventId, ThreadGroup, TimelineEvent, color_from_label, group_total_height,
+    EventId, ThreadGroup, TimelineEvent, group_total_height,
     visible_event_indices_in, visible_shadows_in,
 };
-use crate::data::{ColorMode, display_depth};
+use crate::data::{ColorMode, TreeDirection, display_depth};
+use crate::ui::shade;
 
 // Small helper struct to avoid too_many_arguments lint on the drawing helper.
 struct DrawEventRectArgs<'a> {
@@ -19,8 +31,10 @@ struct DrawEventRectArgs<'a> {
     x: f32,
     width: f32,
     y: f32,
+    lane_height: f64,
     color: Color,
     label: &'a str,
+    text_color: Color,
     is_root: bool,
     is_shadow: bool,
     bounds: Rectangle,
@@ -32,8 +46,10 @@ fn draw_event_rect(args: DrawEventRectArgs<'_>) {
         x,
         width,
         y,
+        lane_height,
         color,
         label,
+        text_color,
         is_root,
         is_shadow,
         bounds,
@@ -42,7 +58,7 @@ fn draw_event_rect(args: DrawEventRectArgs<'_>) {
         x,
         y: y + 1.0,
         width: width.max(1.0),
-        height: (LANE_HEIGHT - 2.0) as f32,
+        height: (lane_height - 2.0) as f32,
     };
 
     frame.fill_rectangle(rect.position(), rect.size(), color);
@@ -62,7 +78,8 @@ fn draw_event_rect(args: DrawEventRectArgs<'_>) {
             .with_width(1.0),
     );
 
-    if rect.width > 5.0 {
+    // Thin lanes leave no room for readable text.
+    if rect.width > 5.0 && lane_height >= MIN_TEXT_LANE_HEIGHT {
         // Draw the full label but intersect the event clip with the overall
         // canvas/layout bounds so text is not drawn outside the visible area.
         let mut clip = Rectangle {
@@ -88,11 +105,7 @@ fn draw_event_rect(args: DrawEventRectArgs<'_>) {
                 frame.fill_text(canvas::Text {
                     content: label.to_string(),
                     position: Point::new(rect.x + 2.0 + EVENT_LEFT_PADDING as f32, rect.y + 2.0),
-                    color: if is_root {
-                        Color::from_rgb(0.35, 0.35, 0.35)
-                    } else {
-                        Color::from_rgb(0.2, 0.2, 0.2)
-                    },
+                    color: text_color,
                     size: 12.0.into(),
                     ..Default::default()
                 });
@@ -101,6 +114,28 @@ fn draw_event_rect(args: DrawEventRectArgs<'_>) {
     }
 }
 
+fn draw_arrow(frame: &mut canvas::Frame, from: Point, to: Point, color: Color) {
+    let stroke = canvas::Stroke::default().with_color(color).with_width(1.5);
+    frame.stroke(&canvas::Path::line(from, to), stroke);
+    frame.fill(&canvas::Path::circle(from, 2.5), color);
+
+    let direction = to - from;
+    let length = (direction.x * direction.x + direction.y * direction.y).sqrt();
+    if length < 1.0 {
+        return;
+    }
+    let (dx, dy) = (direction.x / length, direction.y / length);
+    let head = 7.0;
+    let base = Point::new(to.x - dx * head, to.y - dy * head);
+    let head_path = canvas::Path::new(|path| {
+        path.move_to(to);
+        path.line_to(Point::new(base.x - dy * head * 0.5, base.y + dx * head * 0.5));
+        path.line_to(Point::new(base.x + dy * head * 0.5, base.y - dx * head * 0.5));
+        path.close();
+    });
+    frame.fill(&head_path, color);
+}
+
 pub struct EventsProgram<'a> {
     pub events: &'a [TimelineEvent],
     pub thread_groups: &'a [ThreadGroup],
@@ -108,13 +143,29 @@ pub struct EventsProgram<'a> {
     pub max_ns: u64,
     pub zoom_level: f64,
     pub selected_event: Option<EventId>,
+    /// Events sharing the selected event's label, ordered by start time.
+    pub label_instances: &'a [EventId],
+    /// Absolute ns range picked with the measurement tool.
+    pub measured_range: Option<(u64, u64)>,
+    pub idle_gaps: &'a [crate::analysis::idle_gaps::IdleGap],
+    pub outliers: &'a [crate::analysis::outliers::Outlier],
+    pub query_blocking: &'a crate::analysis::blocking::QueryBlocking,
+    /// Draw every blocked-on arrow instead of only the selected event's.
+    pub show_all_query_links: bool,
     pub scroll_offset_x: f64,
     pub scroll_offset_y: f64,
     pub viewport_width: f64,
     pub viewport_height: f64,
+    /// Height of one depth lane, set by vertical zoom.
+    pub lane_height: f64,
     pub color_mode: ColorMode,
+    pub rule_colors: &'a crate::color_rules::RuleColors,
+    pub event_metrics: Option<&'a crate::analysis::metrics::EventMetrics>,
     pub symbols: &'a crate::symbols::Symbols,
     pub kinds: &'a [crate::data::KindInfo],
+    /// Whether this canvas handles the keyboard shortcuts. Every canvas sees
+    /// every key press, so only one of them may act on it.
+    pub handles_keys: bool,
 }
 
 #[derive(Default)]
@@ -130,22 +181,82 @@ pub struct EventsState {
     pub selecting_right: bool,
     pub selection_start: Option<Point>,
     pub selection_end: Option<Point>,
+    // Shift + left-drag measures a range instead of panning.
+    pub measuring: bool,
+    pub measure_start_x: f32,
+    pub measure_end_x: f32,
 }
 
 impl<'a> EventsProgram<'a> {
-    // Lookup a kind color from the precomputed kinds table by per-event index.
-    // If the index is out of range fall back to deriving a color from the
-    // event label.
-    fn kind_color_from_table(
-        kinds: &'a [crate::data::KindInfo],
-        idx: u16,
-        fallback_label: &str,
-    ) -> iced::Color {
-        kinds
-            .get(idx as usize)
-            .map(|k| k.color)
-            .unwrap_or_else(|| color_from_label(fallback_label))
+    // Map a key press to a navigation message. Without a selection the arrow
+    // keys pan; with one they walk the call tree of the selected event.
+    fn key_message(
+        &self,
+        state: &EventsState,
+        key: &keyboard::Key,
+        modifiers: keyboard::Modifiers,
+    ) -> Option<Message> {
+        use keyboard::Key;
+        use keyboard::key::Named;
+
+        // Leave shortcuts with modifiers (e.g. Alt+Left for history) to the app.
+        if modifiers.alt() || modifiers.control() || modifiers.logo() {
+            return None;
+        }
+
+        let pan_step = (self.viewport_width * 0.1).max(1.0) as f32;
+        let lane_step = (self.lane_height * 3.0) as f32;
+        let pan = |x: f32, y: f32| Message::TimelinePanned {
+            delta: Vector::new(x, y),
+        };
+        let zoom = |delta: f32| Message::TimelineZoomed {
+            delta,
+            x: self.zoom_anchor_x(state),
+        };
+        let has_selection = self.selected_event.is_some();
+
+        let message = match key.as_ref() {
+            Key::Character(c) if c.eq_ignore_ascii_case("w") || c == "+" || c == "=" => zoom(1.0),
+            Key::Character(c) if c.eq_ignore_ascii_case("s") || c == "-" => zoom(-1.0),
+            Key::Character(c) if c.eq_ignore_ascii_case("a") => pan(pan_step, 0.0),
+            Key::Character(c) if c.eq_ignore_ascii_case("d") => pan(-pan_step, 0.0),
+            Key::Named(Named::ArrowLeft) if has_selection => {
+                Message::SelectionMoved(TreeDirection::PreviousSibling)
+            }
+            Key::Named(Named::ArrowRight) if has_selection => {
+                Message::SelectionMoved(TreeDirection::NextSibling)
+            }
+            Key::Named(Named::ArrowUp) if has_selection => {
+                Message::SelectionMoved(TreeDirection::Parent)
+            }
+            Key::Named(Named::ArrowDown) if has_selection => {
+                Message::SelectionMoved(TreeDirection::FirstChild)
+            }
+            Key::Named(Named::ArrowLeft) => pan(pan_step, 0.0),
+            Key::Named(Named::ArrowRight) => pan(-pan_step, 0.0),
+            Key::Named(Named::ArrowUp) => pan(0.0, lane_step),
+            Key::Named(Named::ArrowDown) => pan(0.0, -lane_step),
+            _ => return None,
+        };
+        Some(message)
+    }
+
+    // Keyboard zoom keeps the point under the cursor fixed, falling back to
+    // the middle of the selected event and then to the viewport center.
+    fn zoom_anchor_x(&self, state: &EventsState) -> f32 {
+        let viewport_width = self.viewport_width.max(0.0);
+        if let Some(position) = state.hovered_position {
+            return (position.x as f64).clamp(0.0, viewport_width) as f32;
+        }
+        if let Some(event) = self.selected_event.and_then(|id| self.events.get(id.index())) {
+            let start_ns = event.start_ns.saturating_sub(self.min_ns) as f64;
+            let center_ns = start_ns + event.duration_ns as f64 / 2.0;
+            let x = (center_ns - self.scroll_offset_x) * self.zoom_level;
+            return x.clamp(0.0, viewport_width) as f32;
+        }
+        (viewport_width / 2.0) as f32
     }
+
     fn find_event_at(&self, position: Point) -> Option<EventId> {
         let zoom_level = self.zoom_level.max(1e-9);
         let scroll_offset_x_ns = self.scroll_offset_x.max(0.0);
@@ -164,7 +275,7 @@ impl<'a> EventsProgram<'a> {
 
         let mut y_offset: f64 = 0.0;
         for group in self.thread_groups {
-            let lane_total_height = group_total_height(group);
+            let lane_total_height = group_total_height(group, self.lane_height);
 
             if content_y >= y_offset && content_y < y_offset + lane_total_height {
                 let (ns_min, ns_max) = crate::timeline::viewport_ns_range(
@@ -197,9 +308,9 @@ impl<'a> EventsProgram<'a> {
                             }
                             let x = screen_x(event.start_ns);
                             let y = y_offset as f32 - self.scroll_offset_y as f32
-                                + depth as f32 * (LANE_HEIGHT as f32)
+                                + depth as f32 * (self.lane_height as f32)
                                 + 1.0;
-                            let height = (LANE_HEIGHT - 2.0) as f32;
+                            let height = (self.lane_height - 2.0) as f32;
                             let rect = Rectangle {
                                 x,
                                 y,
@@ -237,9 +348,9 @@ impl<'a> EventsProgram<'a> {
 
                             let x = screen_x(event.start_ns);
                             let y = y_offset as f32 - self.scroll_offset_y as f32
-                                + depth as f32 * (LANE_HEIGHT as f32)
+                                + depth as f32 * (self.lane_height as f32)
                                 + 1.0;
-                            let height = (LANE_HEIGHT - 2.0) as f32;
+                            let height = (self.lane_height - 2.0) as f32;
 
                             let rect = Rectangle {
                                 x,
@@ -268,7 +379,7 @@ impl<'a> Program<Message> for EventsProgram<'a> {
         &self,
         state: &Self::State,
         renderer: &Renderer,
-        _theme: &Theme,
+        theme: &Theme,
         bounds: Rectangle,
         _cursor: mouse::Cursor,
     ) -> Vec<Geometry> {
@@ -309,6 +420,17 @@ impl<'a> Program<Message> for EventsProgram<'a> {
         let rel_max = (scroll_offset_x_ns + viewport_width / zoom_level).max(0.0) as u64;
         let ns_min = self.min_ns.saturating_add(rel_min);
         let ns_max = self.min_ns.saturating_add(rel_max);
+        // Thread roots are drawn as neutral bands in the theme's greys.
+        let thread_root_color = shade(theme, 0.13);
+        let root_text_color = shade(theme, 0.65);
+        let event_colors = EventColors {
+            mode: self.color_mode,
+            rules: self.rule_colors,
+            kinds: self.kinds,
+            symbols: self.symbols,
+            metrics: self.event_metrics,
+            visible_ns: viewport_width / zoom_level,
+        };
 
         // Convert an absolute timestamp (ns) into a screen-space x position.
         // Do the subtraction in ns first to avoid catastrophic cancellation when
@@ -349,7 +471,10 @@ impl<'a> Program<Message> for EventsProgram<'a> {
                         Point::new(x_screen, bounds.height),
                     ),
                     canvas::Stroke::default()
-                        .with_color(Color::from_rgba(0.5, 0.5, 0.5, 0.3))
+                        .with_color(Color {
+                            a: 0.3,
+                            ..shade(theme, 0.5)
+                        })
                         .with_width(1.0),
                 );
 
@@ -372,8 +497,66 @@ impl<'a> Program<Message> for EventsProgram<'a> {
             self.thread_groups.iter().position(|g| super::group_contains_thread(g, tid))
         });
 
+        let group_of_thread: std::collections::HashMap<u32, usize> =
+            if self.label_instances.is_empty()
+                && self.idle_gaps.is_empty()
+                && self.outliers.is_empty()
+            {
+                Default::default()
+            } else {
+                self.thread_groups
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(idx, group)| group.threads.iter().map(move |t| (t.thread_id, idx)))
+                    .collect()
+            };
+
+        // Bucket the visible idle gaps by group.
+        let mut gaps_by_group: Vec<Vec<(u64, u64)>> = vec![Vec::new(); self.thread_groups.len()];
+        for gap in self.idle_gaps {
+            if gap.end_ns < ns_min || gap.start_ns > ns_max {
+                continue;
+            }
+            if let Some(&idx) = group_of_thread.get(&gap.thread_id) {
+                gaps_by_group[idx].push((gap.start_ns, gap.end_ns));
+            }
+        }
+
+        // Bucket the visible instances of the selected label and outliers by
+        // group, with their outline color.
+        let mut instances_by_group: Vec<Vec<(EventId, Color)>> =
+            vec![Vec::new(); self.thread_groups.len()];
+        for outlier in self.outliers {
+            let event = &self.events[outlier.event.index()];
+            if Some(outlier.event) == self.selected_event
+                || event.start_ns > ns_max
+                || event.start_ns.saturating_add(event.duration_ns) < ns_min
+            {
+                continue;
+            }
+            if let Some(&idx) = group_of_thread.get(&event.thread_id) {
+                instances_by_group[idx].push((outlier.event, OUTLIER_COLOR));
+            }
+        }
+        if !self.label_instances.is_empty() {
+            let visible_end = self
+                .label_instances
+                .partition_point(|id| self.events[id.index()].start_ns <= ns_max);
+            for &id in &self.label_instances[..visible_end] {
+                let event = &self.events[id.index()];
+                if Some(id) == self.selected_event
+                    || event.start_ns.saturating_add(event.duration_ns) < ns_min
+                {
+                    continue;
+                }
+                if let Some(&idx) = group_of_thread.get(&event.thread_id) {
+                    instances_by_group[idx].push((id, LABEL_INSTANCE_COLOR));
+                }
+            }
+        }
+
         for (group_idx, group) in self.thread_groups.iter().enumerate() {
-            let lane_total_height = group_total_height(group);
+            let lane_total_height = group_total_height(group, self.lane_height);
 
             // Skip drawing if thread is completely outside vertical viewport
             if self.viewport_height > 0.0
@@ -387,10 +570,22 @@ impl<'a> Program<Message> for EventsProgram<'a> {
             base_frame.stroke(
                 &canvas::Path::line(Point::new(0.0, row_y), Point::new(bounds.width, row_y)),
                 canvas::Stroke::default()
-                    .with_color(Color::from_rgb(0.9, 0.9, 0.9))
+                    .with_color(shade(theme, 0.1))
                     .with_width(1.0),
             );
 
+            for &(start_ns, end_ns) in &gaps_by_group[group_idx] {
+                let x0 = screen_x(start_ns).max(-1.0);
+                let x1 = screen_x(end_ns).min(bounds.width + 1.0);
+                if x1 > x0 {
+                    base_frame.fill_rectangle(
+                        Point::new(x0, row_y),
+                        Size::new(x1 - x0, lane_total_height as f32),
+                        IDLE_GAP_COLOR,
+                    );
+                }
+            }
+
             for thread in group.threads.iter() {
                 if group.show_thread_roots
                     && let Some(root_level) = thread.thread_root_mipmap.as_ref()
@@ -422,19 +617,21 @@ impl<'a> Program<Message> for EventsProgram<'a> {
                             continue;
                         }
 
-                        // Thread-root events use a fixed light color.
-                        let color = THREAD_ROOT_COLOR;
+                        // Thread-root events use a fixed neutral color.
+                        let color = thread_root_color;
                         let label = self.symbols.resolve(event.label);
 
                         let y_screen = y_offset as f32 - self.scroll_offset_y as f32
-                            + depth as f32 * (LANE_HEIGHT as f32);
+                            + depth as f32 * (self.lane_height as f32);
                         draw_event_rect(DrawEventRectArgs {
                             frame: &mut base_frame,
                             x: x_screen,
                             width,
                             y: y_screen,
+                            lane_height: self.lane_height,
                             color,
                             label,
+                            text_color: root_text_color,
                             is_root: true,
                             is_shadow: false,
                             bounds: visible_bounds,
@@ -480,33 +677,29 @@ impl<'a> Program<Message> for EventsProgram<'a> {
                         }
 
                         let color = if event.is_thread_root {
-                            // Thread roots use a fixed light color
-                            THREAD_ROOT_COLOR
+                            // Thread roots use a fixed neutral color
+                            thread_root_color
                         } else {
-                            match self.color_mode {
-                                ColorMode::Kind => Self::kind_color_from_table(
-                                    self.kinds,
-                                    event.kind_index,
-                                    self.symbols.resolve(event.label),
-                                ),
-                                ColorMode::Event => {
-                                    let label = self.symbols.resolve(event.label);
-                                    color_from_label(label)
-                                }
-                            }
+                            event_colors.color(event_id, event)
                         };
                         let label = self.symbols.resolve(event.label);
                         let is_thread_root = event.is_thread_root;
 
                         let y_screen = y_offset as f32 - self.scroll_offset_y as f32
-                            + depth as f32 * (LANE_HEIGHT as f32);
+                            + depth as f32 * (self.lane_height as f32);
                         draw_event_rect(DrawEventRectArgs {
                             frame: &mut base_frame,
                             x: x_screen,
                             width,
                             y: y_screen,
+                            lane_height: self.lane_height,
                             color,
                             label,
+                            text_color: if is_thread_root {
+                                root_text_color
+                            } else {
+                                contrast_text_color(color)
+                            },
                             is_root: is_thread_root,
                             is_shadow: false,
                             bounds: visible_bounds,
@@ -542,16 +735,21 @@ impl<'a> Program<Message> for EventsProgram<'a> {
                             continue;
                         }
 
-                        let color = Color::from_rgba(0.0, 0.0, 0.0, 0.10);
+                        let color = Color {
+                            a: 0.10,
+                            ..shade(theme, 1.0)
+                        };
                         let y_screen = y_offset as f32 - self.scroll_offset_y as f32
-                            + adjusted_depth as f32 * (LANE_HEIGHT as f32);
+                            + adjusted_depth as f32 * (self.lane_height as f32);
                         draw_event_rect(DrawEventRectArgs {
                             frame: &mut base_frame,
                             x: x_screen,
                             width,
                             y: y_screen,
+                            lane_height: self.lane_height,
                             color,
                             label: "",
+                            text_color: root_text_color,
                             is_root: false,
                             is_shadow: true,
                             bounds: visible_bounds,
@@ -560,6 +758,35 @@ impl<'a> Program<Message> for EventsProgram<'a> {
                 }
             }
 
+            for &(instance_id, outline_color) in &instances_by_group[group_idx] {
+                let instance = &self.events[instance_id.index()];
+                let depth = display_depth(group.show_thread_roots, instance);
+                if group.is_collapsed && depth > 0 {
+                    continue;
+                }
+                // Keep tiny instances visible even when they are below the
+                // mipmap cutoff and were not drawn above.
+                let width = (crate::timeline::duration_to_width(instance.duration_ns, zoom_level)
+                    as f32)
+                    .max(2.0);
+                let x_screen = screen_x(instance.start_ns);
+                let y = y_offset as f32 - self.scroll_offset_y as f32
+                    + depth as f32 * (self.lane_height as f32);
+                let position = Point::new(x_screen, y + 1.0);
+                let size = Size::new(width, (self.lane_height - 2.0) as f32);
+                let fill = Color {
+                    a: 0.25,
+                    ..outline_color
+                };
+                base_frame.fill_rectangle(position, size, fill);
+                base_frame.stroke(
+                    &canvas::Path::rectangle(position, size),
+                    canvas::Stroke::default()
+                        .with_color(outline_color)
+                        .with_width(2.0),
+                );
+            }
+
             if hovered_group_idx == Some(group_idx) {
                 let hovered_id = state.hovered_event.unwrap();
                 let hovered = &self.events[hovered_id.index()];
@@ -570,15 +797,18 @@ impl<'a> Program<Message> for EventsProgram<'a> {
                             as f32;
                     let x_screen = screen_x(hovered.start_ns);
                     let y = y_offset as f32 - self.scroll_offset_y as f32
-                        + hovered_depth as f32 * (LANE_HEIGHT as f32);
+                        + hovered_depth as f32 * (self.lane_height as f32);
 
                     base_frame.stroke(
                         &canvas::Path::rectangle(
                             Point::new(x_screen, y + 1.0),
-                            Size::new(width.max(1.0), (LANE_HEIGHT - 2.0) as f32),
+                            Size::new(width.max(1.0), (self.lane_height - 2.0) as f32),
                         ),
                         canvas::Stroke::default()
-                            .with_color(Color::from_rgba(0.0, 0.0, 0.0, 0.3))
+                            .with_color(Color {
+                                a: 0.3,
+                                ..shade(theme, 1.0)
+                            })
                             .with_width(1.0),
                     );
                 }
@@ -594,12 +824,12 @@ impl<'a> Program<Message> for EventsProgram<'a> {
                             as f32;
                     let x_screen = screen_x(selected.start_ns);
                     let y = y_offset as f32 - self.scroll_offset_y as f32
-                        + selected_depth as f32 * (LANE_HEIGHT as f32);
+                        + selected_depth as f32 * (self.lane_height as f32);
 
                     base_frame.stroke(
                         &canvas::Path::rectangle(
                             Point::new(x_screen, y + 1.0),
-                            Size::new(width.max(1.0), (LANE_HEIGHT - 2.0) as f32),
+                            Size::new(width.max(1.0), (self.lane_height - 2.0) as f32),
                         ),
                         canvas::Stroke::default()
                             .with_color(Color::from_rgb(0.0, 0.4, 0.8))
@@ -611,34 +841,101 @@ impl<'a> Program<Message> for EventsProgram<'a> {
             y_offset += lane_total_height + super::LANE_SPACING;
         }
 
+        // Arrows from blocked queries to the execution they wait on, drawn
+        // from the start of the wait.
+        let lane_center = |event: &TimelineEvent| {
+            super::event_lane_y(self.thread_groups, event, self.lane_height).map(|y| {
+                (y - self.scroll_offset_y + self.lane_height / 2.0) as f32
+            })
+        };
+        let is_visible = |event: &TimelineEvent| {
+            event.start_ns <= ns_max && event.start_ns.saturating_add(event.duration_ns) >= ns_min
+        };
+        for query in &self.query_blocking.blocked {
+            let Some(executing_id) = query.executing else {
+                continue;
+            };
+            if !self.show_all_query_links
+                && self.selected_event != Some(query.blocked)
+                && self.selected_event != Some(executing_id)
+            {
+                continue;
+            }
+            let blocked = &self.events[query.blocked.index()];
+            let executing = &self.events[executing_id.index()];
+            if !is_visible(blocked) && !is_visible(executing) {
+                continue;
+            }
+            let (Some(from_y), Some(to_y)) = (lane_center(blocked), lane_center(executing)) else {
+                continue;
+            };
+            let from = Point::new(screen_x(blocked.start_ns), from_y);
+            let to_ns = blocked.start_ns.clamp(
+                executing.start_ns,
+                executing.start_ns.saturating_add(executing.duration_ns),
+            );
+            let to = Point::new(screen_x(to_ns), to_y);
+            draw_arrow(&mut base_frame, from, to, QUERY_LINK_COLOR);
+        }
+
         // Tooltip is now a widget overlay (see `src/tooltip.rs`).
 
-        // Draw right-button selection rectangle (above base)
-        if state.selecting_right {
-            if let (Some(start), Some(end)) = (state.selection_start, state.selection_end) {
-                let raw_x_start = start.x.min(end.x);
-                let raw_x_end = start.x.max(end.x);
-                let x_start = raw_x_start.max(0.0).min(bounds.width);
-                let x_end = raw_x_end.max(0.0).min(bounds.width);
-                let width = (x_end - x_start).max(0.0);
-                if width >= 1.0 {
-                    let rect_pos = Point::new(x_start, 0.0);
-                    let rect_size = Size::new(width, bounds.height);
-                    let mut sel_frame = canvas::Frame::new(renderer, bounds.size());
-                    sel_frame.fill_rectangle(rect_pos, rect_size, Color::from_rgba(0.2, 0.4, 0.6, 0.15));
-                    sel_frame.stroke(
-                        &canvas::Path::rectangle(rect_pos, rect_size),
+        // Shade the measured range, or the one being dragged out.
+        let measured_x = if state.measuring {
+            Some((
+                state.measure_start_x.min(state.measure_end_x),
+                state.measure_start_x.max(state.measure_end_x),
+            ))
+        } else {
+            self.measured_range
+                .map(|(start_ns, end_ns)| (screen_x(start_ns), screen_x(end_ns)))
+        };
+        if let Some((x0, x1)) = measured_x {
+            let x0 = x0.max(-1.0);
+            let x1 = x1.min(bounds.width + 1.0);
+            if x1 > x0 {
+                base_frame.fill_rectangle(
+                    Point::new(x0, 0.0),
+                    Size::new(x1 - x0, bounds.height),
+                    super::MEASURE_FILL_COLOR,
+                );
+                for x in [x0, x1] {
+                    base_frame.stroke(
+                        &canvas::Path::line(Point::new(x, 0.0), Point::new(x, bounds.height)),
                         canvas::Stroke::default()
-                            .with_color(Color::from_rgba(0.2, 0.4, 0.6, 0.6))
+                            .with_color(super::MEASURE_EDGE_COLOR)
                             .with_width(1.0),
                     );
-                    let sel_geom = sel_frame.into_geometry();
-                    // Return geometry layers: base, selection.
-                    return vec![base_frame.into_geometry(), sel_geom];
                 }
             }
         }
 
+        // Draw right-button selection rectangle (above base)
+        if state.selecting_right
+            && let (Some(start), Some(end)) = (state.selection_start, state.selection_end)
+        {
+            let raw_x_start = start.x.min(end.x);
+            let raw_x_end = start.x.max(end.x);
+            let x_start = raw_x_start.max(0.0).min(bounds.width);
+            let x_end = raw_x_end.max(0.0).min(bounds.width);
+            let width = (x_end - x_start).max(0.0);
+            if width >= 1.0 {
+                let rect_pos = Point::new(x_start, 0.0);
+                let rect_size = Size::new(width, bounds.height);
+                let mut sel_frame = canvas::Frame::new(renderer, bounds.size());
+                sel_frame.fill_rectangle(rect_pos, rect_size, Color::from_rgba(0.2, 0.4, 0.6, 0.15));
+                sel_frame.stroke(
+                    &canvas::Path::rectangle(rect_pos, rect_size),
+                    canvas::Stroke::default()
+                        .with_color(Color::from_rgba(0.2, 0.4, 0.6, 0.6))
+                        .with_width(1.0),
+                );
+                let sel_geom = sel_frame.into_geometry();
+                // Return geometry layers: base, selection.
+                return vec![base_frame.into_geometry(), sel_geom];
+            }
+        }
+
         vec![base_frame.into_geometry()]
     }
 
@@ -653,7 +950,46 @@ impl<'a> Program<Message> for EventsProgram<'a> {
             iced::Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
                 state.modifiers = *modifiers;
             }
+            iced::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. })
+                if self.handles_keys =>
+            {
+                return self
+                    .key_message(state, key, *modifiers)
+                    .map(|message| canvas::Action::publish(message).and_capture());
+            }
             
+            iced::Event::Mouse(iced::mouse::Event::ButtonPressed(iced::mouse::Button::Left))
+                if state.modifiers.shift() =>
+            {
+                if let Some(position) = cursor.position_in(bounds) {
+                    state.measuring = true;
+                    state.measure_start_x = position.x;
+                    state.measure_end_x = position.x;
+                    // Capture so the surrounding pan handler does not start a pan.
+                    return Some(canvas::Action::request_redraw().and_capture());
+                }
+            }
+            iced::Event::Mouse(iced::mouse::Event::CursorMoved { .. }) if state.measuring => {
+                if let Some(position) = cursor.position_in(bounds) {
+                    state.measure_end_x = position.x;
+                }
+                return Some(canvas::Action::request_redraw().and_capture());
+            }
+            iced::Event::Mouse(iced::mouse::Event::ButtonReleased(iced::mouse::Button::Left))
+                if state.measuring =>
+            {
+                state.measuring = false;
+                let start_x = state.measure_start_x.min(state.measure_end_x);
+                let end_x = state.measure_start_x.max(state.measure_end_x);
+                if end_x - start_x < super::DRAG_THRESHOLD as f32 {
+                    return Some(canvas::Action::request_redraw());
+                }
+                let zoom_level = self.zoom_level.max(1e-9);
+                return Some(canvas::Action::publish(Message::RangeSelected {
+                    start_ns: start_x as f64 / zoom_level + self.scroll_offset_x,
+                    end_ns: end_x as f64 / zoom_level + self.scroll_offset_x,
+                }));
+            }
             iced::Event::Mouse(iced::mouse::Event::ButtonPressed(iced::mouse::Button::Left)) => {
                 if let Some(position) = cursor.position_in(bounds) {
                     state.press_position = cursor.position();
@@ -749,36 +1085,53 @@ impl<'a> Program<Message> for EventsProgram<'a> {
                     }));
                 }
             }
-            iced::Event::Mouse(iced::mouse::Event::ButtonReleased(iced::mouse::Button::Right)) => {
-                if state.selecting_right {
-                    state.selecting_right = false;
-                    if let (Some(start), Some(end)) = (state.selection_start, state.selection_end)
-                        && (start.x - end.x).abs() >= 4.0
-                    {
-                        // Compute ns range from screen x positions
-                        let zoom_level = self.zoom_level.max(1e-9);
-                        let px_start = start.x.min(end.x).max(0.0);
-                        let px_end = start.x.max(end.x).max(0.0);
-                        let rel_start_ns = px_start as f64 / zoom_level + self.scroll_offset_x;
-                        let rel_end_ns = px_end as f64 / zoom_level + self.scroll_offset_x;
-                        // Publish zoom-to-range using ns relative to timeline min
-                        return Some(
-                            canvas::Action::publish(Message::TimelineZoomTo {
-                                start_ns: rel_start_ns,
-                                end_ns: rel_end_ns,
-                            })
-                            .and_capture(),
-                        );
-                    }
-                    state.selection_start = None;
-                    state.selection_end = None;
-                    return Some(canvas::Action::publish(Message::None));
+            iced::Event::Mouse(iced::mouse::Event::ButtonReleased(iced::mouse::Button::Right))
+                if state.selecting_right =>
+            {
+                state.selecting_right = false;
+                if let (Some(start), Some(end)) = (state.selection_start, state.selection_end)
+                    && (start.x - end.x).abs() >= 4.0
+                {
+                    // Compute ns range from screen x positions
+                    let zoom_level = self.zoom_level.max(1e-9);
+                    let px_start = start.x.min(end.x).max(0.0);
+                    let px_end = start.x.max(end.x).max(0.0);
+                    let rel_start_ns = px_start as f64 / zoom_level + self.scroll_offset_x;
+                    let rel_end_ns = px_end as f64 / zoom_level + self.scroll_offset_x;
+                    // Publish zoom-to-range using ns relative to timeline min
+                    return Some(
+                        canvas::Action::publish(Message::TimelineZoomTo {
+                            start_ns: rel_start_ns,
+                            end_ns: rel_end_ns,
+                        })
+                        .and_capture(),
+                    );
                 }
+                state.selection_start = None;
+                state.selection_end = None;
+                return Some(canvas::Action::publish(Message::None));
             }
             iced::Event::Mouse(iced::mouse::Event::WheelScrolled { delta }) => {
                 if let Some(position) = cursor.position_in(bounds) {
+                    // Ctrl + Shift + wheel: vertical zoom (lane height)
+                    if state.modifiers.shift() && state.modifiers.control() {
+                        match delta {
+                            iced::mouse::ScrollDelta::Lines { x: _, y }
+                            | iced::mouse::ScrollDelta::Pixels { x: _, y } => {
+                                if y.abs() > 0.0 {
+                                    let lane_height =
+                                        self.lane_height * 1.1_f64.powf(y.signum() as f64);
+                                    return Some(
+                                        canvas::Action::publish(Message::LaneHeightChanged(
+                                            lane_height,
+                                        ))
+                                        .and_capture(),
+                                    );
+                                }
+                            }
+                        }
                     // Shift + wheel: pan horizontally
-                    if state.modifiers.shift() {
+                    } else if state.modifiers.shift() {
                         match delta {
                             iced::mouse::ScrollDelta::Lines { x: _, y }
                             | iced::mouse::ScrollDelta::Pixels { x: _, y } => {
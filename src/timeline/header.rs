@@ -1,25 +1,42 @@
 // Header uses explicit f64 scroll offsets passed from the application state.
 use crate::Message;
 use crate::timeline::ticks::nice_interval;
+use crate::ui::shade;
 use iced::mouse;
-use iced::widget::canvas::{self, Geometry, Program};
-use iced::{Color, Point, Rectangle, Renderer, Size, Theme};
+use iced::widget::canvas::{self, Action, Geometry, Program};
+use iced::{Event, Point, Rectangle, Renderer, Size, Theme};
 
 pub(crate) struct HeaderProgram {
     pub(crate) min_ns: u64,
     pub(crate) max_ns: u64,
     pub(crate) zoom_level: f64,
     pub(crate) scroll_offset_x: f64,
+    /// Absolute ns range picked with the measurement tool.
+    pub(crate) measured_range: Option<(u64, u64)>,
+}
+
+/// Dragging in the header measures a time range.
+#[derive(Default)]
+pub(crate) struct HeaderState {
+    drag_start: Option<f32>,
+    drag_end: Option<f32>,
+}
+
+impl HeaderProgram {
+    // Convert a header x position into ns relative to the timeline start.
+    fn relative_ns_at(&self, x: f32) -> f64 {
+        (x as f64 + self.scroll_offset_x) / self.zoom_level.max(1e-9)
+    }
 }
 
 impl Program<Message> for HeaderProgram {
-    type State = ();
+    type State = HeaderState;
 
     fn draw(
         &self,
-        _state: &Self::State,
+        state: &Self::State,
         renderer: &Renderer,
-        _theme: &Theme,
+        theme: &Theme,
         bounds: Rectangle,
         _cursor: mouse::Cursor,
     ) -> Vec<Geometry> {
@@ -28,7 +45,7 @@ impl Program<Message> for HeaderProgram {
         frame.fill_rectangle(
             Point::new(0.0, 0.0),
             Size::new(bounds.width, bounds.height),
-            Color::from_rgb(0.95, 0.95, 0.95),
+            shade(theme, 0.05),
         );
 
         let total_ns = crate::timeline::total_ns(self.min_ns, self.max_ns) as f64;
@@ -104,7 +121,7 @@ impl Program<Message> for HeaderProgram {
             frame.fill_text(canvas::Text {
                 content: s_str,
                 position: Point::new(x + 2.0, y1),
-                color: Color::from_rgb(0.2, 0.2, 0.2),
+                color: shade(theme, 0.8),
                 size: 11.0.into(),
                 ..Default::default()
             });
@@ -115,7 +132,7 @@ impl Program<Message> for HeaderProgram {
             frame.fill_text(canvas::Text {
                 content: ms_str,
                 position: Point::new(x + 2.0, y2),
-                color: Color::from_rgb(0.3, 0.3, 0.3),
+                color: shade(theme, 0.7),
                 size: 11.0.into(),
                 ..Default::default()
             });
@@ -127,7 +144,7 @@ impl Program<Message> for HeaderProgram {
             frame.fill_text(canvas::Text {
                 content: us_str,
                 position: Point::new(x + 2.0, y3),
-                color: Color::from_rgb(0.4, 0.4, 0.4),
+                color: shade(theme, 0.6),
                 size: 11.0.into(),
                 ..Default::default()
             });
@@ -137,11 +154,11 @@ impl Program<Message> for HeaderProgram {
             let is_ms_tick = ns_total.is_multiple_of(1_000_000);
             // Darken header tick lines to increase contrast against the light background.
             let (tick_color, tick_width) = if is_second_tick {
-                (Color::from_rgb(0.18, 0.18, 0.18), 1.0)
+                (shade(theme, 0.82), 1.0)
             } else if is_ms_tick {
-                (Color::from_rgb(0.36, 0.36, 0.36), 0.8)
+                (shade(theme, 0.64), 0.8)
             } else {
-                (Color::from_rgb(0.55, 0.55, 0.55), 0.5)
+                (shade(theme, 0.45), 0.5)
             };
             frame.stroke(
                 &canvas::Path::line(Point::new(x, 0.0), Point::new(x, bounds.height)),
@@ -153,6 +170,35 @@ impl Program<Message> for HeaderProgram {
             relative_ns += nice_interval;
         }
 
+        let shaded = match (state.drag_start, state.drag_end) {
+            (Some(start), Some(end)) => Some((start.min(end), start.max(end))),
+            _ => self.measured_range.map(|(start_ns, end_ns)| {
+                let x = |ns: u64| screen_x(ns.saturating_sub(self.min_ns) as f64);
+                (x(start_ns), x(end_ns))
+            }),
+        };
+        if let Some((x0, x1)) = shaded {
+            let x0 = x0.max(0.0);
+            let x1 = x1.min(bounds.width);
+            if x1 > x0 {
+                frame.fill_rectangle(
+                    Point::new(x0, 0.0),
+                    Size::new(x1 - x0, bounds.height),
+                    super::MEASURE_FILL_COLOR,
+                );
+                let duration_ns = (self.relative_ns_at(x1) - self.relative_ns_at(x0)).max(0.0);
+                frame.fill_text(canvas::Text {
+                    content: super::format_duration(duration_ns as u64),
+                    position: Point::new((x0 + x1) / 2.0, bounds.height - 4.0),
+                    color: super::MEASURE_EDGE_COLOR,
+                    size: 11.0.into(),
+                    align_x: iced::widget::text::Alignment::Center,
+                    align_y: iced::alignment::Vertical::Bottom,
+                    ..Default::default()
+                });
+            }
+        }
+
         // Draw separator lines between layers
         frame.stroke(
             &canvas::Path::line(
@@ -160,7 +206,7 @@ impl Program<Message> for HeaderProgram {
                 Point::new(bounds.width, layer_height),
             ),
             canvas::Stroke::default()
-                .with_color(Color::from_rgb(0.85, 0.85, 0.85))
+                .with_color(shade(theme, 0.15))
                 .with_width(0.5),
         );
         frame.stroke(
@@ -169,10 +215,60 @@ impl Program<Message> for HeaderProgram {
                 Point::new(bounds.width, layer_height * 2.0),
             ),
             canvas::Stroke::default()
-                .with_color(Color::from_rgb(0.85, 0.85, 0.85))
+                .with_color(shade(theme, 0.15))
                 .with_width(0.5),
         );
 
         vec![frame.into_geometry()]
     }
+
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<Action<Message>> {
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let position = cursor.position_in(bounds)?;
+                state.drag_start = Some(position.x);
+                state.drag_end = Some(position.x);
+                // Keep the surrounding pan handler from treating this as a pan.
+                return Some(Action::capture());
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) if state.drag_start.is_some() => {
+                state.drag_end = Some((position.x - bounds.x).clamp(0.0, bounds.width));
+                return Some(Action::request_redraw().and_capture());
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                let (Some(start), Some(end)) = (state.drag_start.take(), state.drag_end.take())
+                else {
+                    return None;
+                };
+                if (start - end).abs() < super::DRAG_THRESHOLD as f32 {
+                    return Some(Action::request_redraw());
+                }
+                return Some(Action::publish(Message::RangeSelected {
+                    start_ns: self.relative_ns_at(start.min(end)),
+                    end_ns: self.relative_ns_at(start.max(end)),
+                }));
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn mouse_interaction(
+        &self,
+        _state: &Self::State,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        if cursor.is_over(bounds) {
+            mouse::Interaction::ResizingHorizontally
+        } else {
+            mouse::Interaction::default()
+        }
+    }
 }
@@ -0,0 +1,195 @@
+// Duration histogram of the selected event's label in the details panel.
+use super::format_duration;
+use crate::Message;
+use crate::analysis::histogram::DurationHistogram;
+use crate::ui::shade;
+use iced::mouse;
+use iced::widget::canvas::{self, Action, Geometry, Program};
+use iced::{Color, Event, Point, Rectangle, Renderer, Size, Theme};
+
+pub(crate) const HISTOGRAM_WIDTH: f32 = 360.0;
+pub(crate) const HISTOGRAM_HEIGHT: f32 = 64.0;
+
+/// Space below the bars for the duration axis labels.
+const AXIS_HEIGHT: f32 = 14.0;
+
+const BAR_COLOR: Color = Color::from_rgb(0.6, 0.68, 0.78);
+const CURRENT_BAR_COLOR: Color = Color::from_rgb(0.0, 0.4, 0.8);
+const SELECTED_BUCKET_COLOR: Color = Color::from_rgb(0.95, 0.55, 0.1);
+
+pub(crate) struct HistogramProgram<'a> {
+    pub(crate) histogram: &'a DurationHistogram,
+    /// Bucket of the selected event.
+    pub(crate) current_bucket: u32,
+    /// Bucket whose instances are highlighted.
+    pub(crate) selected_bucket: Option<u32>,
+}
+
+#[derive(Default)]
+pub(crate) struct HistogramState {
+    hovered_bucket: Option<u32>,
+}
+
+impl HistogramProgram<'_> {
+    fn bar_width(&self, bounds: Rectangle) -> f32 {
+        bounds.width / self.histogram.counts.len().max(1) as f32
+    }
+
+    fn bucket_at(&self, bounds: Rectangle, cursor: mouse::Cursor) -> Option<u32> {
+        let position = cursor.position_in(bounds)?;
+        let index = (position.x / self.bar_width(bounds)) as usize;
+        (index < self.histogram.counts.len()).then(|| self.histogram.first_bucket + index as u32)
+    }
+}
+
+impl Program<Message> for HistogramProgram<'_> {
+    type State = HistogramState;
+
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<Action<Message>> {
+        match event {
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                let hovered = self.bucket_at(bounds, cursor);
+                if hovered != state.hovered_bucket {
+                    state.hovered_bucket = hovered;
+                    return Some(Action::request_redraw());
+                }
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let bucket = self.bucket_at(bounds, cursor)?;
+                // Clicking the highlighted bucket again clears it.
+                let selected = (self.selected_bucket != Some(bucket)).then_some(bucket);
+                return Some(
+                    Action::publish(Message::HistogramBucketSelected(selected)).and_capture(),
+                );
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn draw(
+        &self,
+        state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        let histogram = self.histogram;
+        let bar_width = self.bar_width(bounds);
+        let bars_height = (bounds.height - AXIS_HEIGHT).max(1.0);
+        let max_count = histogram.max_count.max(1) as f32;
+
+        for (index, &count) in histogram.counts.iter().enumerate() {
+            let bucket = histogram.first_bucket + index as u32;
+            let x = index as f32 * bar_width;
+            if state.hovered_bucket == Some(bucket) {
+                frame.fill_rectangle(
+                    Point::new(x, 0.0),
+                    Size::new(bar_width, bars_height),
+                    Color {
+                        a: 0.05,
+                        ..shade(theme, 1.0)
+                    },
+                );
+            }
+            if count == 0 {
+                continue;
+            }
+            // Keep buckets with few instances visible next to large ones.
+            let height = (count as f32 / max_count * bars_height).max(2.0);
+            let color = if bucket == self.current_bucket {
+                CURRENT_BAR_COLOR
+            } else {
+                BAR_COLOR
+            };
+            let position = Point::new(x + 1.0, bars_height - height);
+            let size = Size::new((bar_width - 2.0).max(1.0), height);
+            frame.fill_rectangle(position, size, color);
+            if self.selected_bucket == Some(bucket) {
+                frame.stroke(
+                    &canvas::Path::rectangle(position, size),
+                    canvas::Stroke::default()
+                        .with_color(SELECTED_BUCKET_COLOR)
+                        .with_width(2.0),
+                );
+            }
+        }
+
+        frame.stroke(
+            &canvas::Path::line(
+                Point::new(0.0, bars_height + 0.5),
+                Point::new(bounds.width, bars_height + 0.5),
+            ),
+            canvas::Stroke::default()
+                .with_color(shade(theme, 0.3))
+                .with_width(1.0),
+        );
+
+        let axis_color = shade(theme, 0.6);
+        let buckets = histogram.buckets();
+        let (first_ns, _) = DurationHistogram::bucket_range_ns(buckets.start);
+        let (_, last_ns) = DurationHistogram::bucket_range_ns(buckets.end - 1);
+        let label_y = bars_height + 2.0;
+        // The hover is only updated when the cursor moves, so it can be left
+        // over from the histogram of a previously selected label.
+        match state.hovered_bucket.filter(|bucket| buckets.contains(bucket)) {
+            Some(bucket) => {
+                let (start_ns, end_ns) = DurationHistogram::bucket_range_ns(bucket);
+                let count = histogram.counts[(bucket - histogram.first_bucket) as usize];
+                frame.fill_text(canvas::Text {
+                    content: format!(
+                        "{} – {}: {} instances",
+                        format_duration(start_ns),
+                        format_duration(end_ns),
+                        count
+                    ),
+                    position: Point::new(bounds.width / 2.0, label_y),
+                    color: axis_color,
+                    size: 10.0.into(),
+                    align_x: iced::widget::text::Alignment::Center,
+                    ..Default::default()
+                });
+            }
+            None => {
+                frame.fill_text(canvas::Text {
+                    content: format_duration(first_ns),
+                    position: Point::new(0.0, label_y),
+                    color: axis_color,
+                    size: 10.0.into(),
+                    ..Default::default()
+                });
+                frame.fill_text(canvas::Text {
+                    content: format_duration(last_ns),
+                    position: Point::new(bounds.width, label_y),
+                    color: axis_color,
+                    size: 10.0.into(),
+                    align_x: iced::widget::text::Alignment::Right,
+                    ..Default::default()
+                });
+            }
+        }
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        _state: &Self::State,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        if self.bucket_at(bounds, cursor).is_some() {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
@@ -1,11 +1,23 @@
 // Mini timeline receives explicit f64 scroll offsets from app state.
 use crate::Message;
+use crate::analysis::density::ActivityDensity;
+use crate::analysis::utilization::Utilization;
+use crate::data::{EventId, TimelineEvent};
 use crate::timeline::ticks::{format_time_label, nice_interval};
+use crate::ui::shade;
 use iced::mouse;
 use iced::widget::canvas::{self, Action, Geometry, Program};
 use iced::{Color, Event, Point, Rectangle, Renderer, Size, Theme};
 
-pub(crate) struct MiniTimelineProgram {
+pub(crate) struct MiniTimelineProgram<'a> {
+    pub(crate) events: &'a [TimelineEvent],
+    /// Precomputed event density drawn as a heatmap background.
+    pub(crate) density: &'a ActivityDensity,
+    pub(crate) selected_event: Option<EventId>,
+    /// Events sharing the selected event's label; drawn as tick marks.
+    pub(crate) label_instances: &'a [EventId],
+    /// Busy thread curve drawn behind the ticks, if enabled.
+    pub(crate) utilization: Option<&'a Utilization>,
     pub(crate) min_ns: u64,
     pub(crate) max_ns: u64,
     pub(crate) zoom_level: f64,
@@ -21,7 +33,7 @@ pub(crate) struct MiniTimelineState {
     dragging: bool,
 }
 
-impl MiniTimelineProgram {
+impl MiniTimelineProgram<'_> {
     fn fallback_viewport_width(&self, bounds: Rectangle) -> f32 {
         bounds.width.max(0.0)
     }
@@ -60,24 +72,24 @@ impl MiniTimelineProgram {
     }
 }
 
-impl Program<Message> for MiniTimelineProgram {
+impl Program<Message> for MiniTimelineProgram<'_> {
     type State = MiniTimelineState;
 
     fn draw(
         &self,
         state: &Self::State,
         renderer: &Renderer,
-        _theme: &Theme,
+        theme: &Theme,
         bounds: Rectangle,
         _cursor: mouse::Cursor,
     ) -> Vec<Geometry> {
         let mut frame = canvas::Frame::new(renderer, bounds.size());
 
-        // Mini timeline background: use white for a clean look.
+        // Mini timeline background: the plain theme background.
         frame.fill_rectangle(
             Point::new(0.0, 0.0),
             Size::new(bounds.width, bounds.height),
-            Color::WHITE,
+            shade(theme, 0.0),
         );
 
         let total_ns = crate::timeline::total_ns(self.min_ns, self.max_ns) as f64;
@@ -86,6 +98,41 @@ impl Program<Message> for MiniTimelineProgram {
         }
 
         let ns_per_pixel = total_ns / bounds.width as f64;
+
+        // Activity heatmap: darker columns contain more events.
+        for (column, heat) in self
+            .density
+            .column_heat(bounds.width.ceil() as usize)
+            .into_iter()
+            .enumerate()
+        {
+            if heat > 0.0 {
+                frame.fill_rectangle(
+                    Point::new(column as f32, 0.0),
+                    Size::new(1.0, bounds.height),
+                    Color::from_rgba(0.95, 0.45, 0.1, heat * 0.5),
+                );
+            }
+        }
+
+        if let Some(utilization) = self.utilization {
+            super::utilization::draw_utilization(
+                &mut frame,
+                utilization,
+                Rectangle {
+                    x: 0.0,
+                    y: 16.0,
+                    width: bounds.width,
+                    height: (bounds.height - 17.0).max(0.0),
+                },
+                self.min_ns as f64,
+                ns_per_pixel,
+                Color {
+                    a: 0.35,
+                    ..super::utilization::UTILIZATION_FILL_COLOR
+                },
+            );
+        }
         let pixel_interval = 120.0;
         let ns_interval = pixel_interval * ns_per_pixel;
         let nice_interval = nice_interval(ns_interval);
@@ -101,7 +148,10 @@ impl Program<Message> for MiniTimelineProgram {
             frame.stroke(
                 &canvas::Path::line(Point::new(x, 0.0), Point::new(x, bounds.height)),
                 canvas::Stroke::default()
-                    .with_color(Color::from_rgba(0.5, 0.5, 0.5, 0.3))
+                    .with_color(Color {
+                        a: 0.3,
+                        ..shade(theme, 0.5)
+                    })
                     .with_width(1.0),
             );
 
@@ -109,7 +159,7 @@ impl Program<Message> for MiniTimelineProgram {
             frame.fill_text(canvas::Text {
                 content: time_str,
                 position: Point::new(x + 2.0, 4.0),
-                color: Color::from_rgb(0.4, 0.4, 0.4),
+                color: shade(theme, 0.6),
                 size: 10.0.into(),
                 ..Default::default()
             });
@@ -145,6 +195,50 @@ impl Program<Message> for MiniTimelineProgram {
             );
         }
 
+        // One tick per pixel column that contains at least one instance.
+        let columns = bounds.width.ceil() as usize;
+        if !self.label_instances.is_empty() && columns > 0 {
+            let mut has_instance = vec![false; columns];
+            for id in self.label_instances {
+                let event = &self.events[id.index()];
+                let fraction = event.start_ns.saturating_sub(self.min_ns) as f64 / total_ns;
+                let column = (fraction * bounds.width as f64) as usize;
+                has_instance[column.min(columns - 1)] = true;
+            }
+            for (column, _) in has_instance.iter().enumerate().filter(|(_, set)| **set) {
+                let x = column as f32 + 0.5;
+                frame.stroke(
+                    &canvas::Path::line(
+                        Point::new(x, bounds.height - 10.0),
+                        Point::new(x, bounds.height - 1.0),
+                    ),
+                    canvas::Stroke::default()
+                        .with_color(Color::from_rgb(0.95, 0.55, 0.1))
+                        .with_width(1.0),
+                );
+            }
+        }
+
+        // Mark where the selected event is in the whole profile.
+        if let Some(event) = self.selected_event.and_then(|id| self.events.get(id.index())) {
+            let x = |ns: u64| {
+                (ns.saturating_sub(self.min_ns) as f64 / total_ns) as f32 * bounds.width
+            };
+            let x0 = x(event.start_ns);
+            let x1 = x(event.start_ns.saturating_add(event.duration_ns)).max(x0 + 2.0);
+            frame.fill_rectangle(
+                Point::new(x0, 0.0),
+                Size::new(x1 - x0, bounds.height),
+                Color::from_rgba(0.0, 0.4, 0.8, 0.35),
+            );
+            frame.stroke(
+                &canvas::Path::line(Point::new(x0, 0.0), Point::new(x0, bounds.height)),
+                canvas::Stroke::default()
+                    .with_color(Color::from_rgb(0.0, 0.4, 0.8))
+                    .with_width(2.0),
+            );
+        }
+
         if let Some(selection) = self.selection_bounds(state, bounds) {
             frame.fill_rectangle(
                 selection.position(),
@@ -167,7 +261,7 @@ impl Program<Message> for MiniTimelineProgram {
                 Point::new(bounds.width, bounds.height - 0.5),
             ),
             canvas::Stroke::default()
-                .with_color(Color::from_rgba(0.6, 0.6, 0.6, 1.0))
+                .with_color(shade(theme, 0.4))
                 .with_width(1.0),
         );
 
@@ -239,28 +333,26 @@ impl Program<Message> for MiniTimelineProgram {
             Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
                 state.dragging = false;
             }
-            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Right)) => {
-                if state.selecting {
-                    state.selecting = false;
-                    if let Some(selection) = self.selection_bounds(state, bounds)
-                        && selection.width >= 4.0
-                    {
-                        let events_width = bounds.width;
-                        let start_fraction = (selection.x / events_width).clamp(0.0, 1.0);
-                        let end_fraction =
-                            ((selection.x + selection.width) / events_width).clamp(0.0, 1.0);
-                        let viewport_width = self.viewport_width_for_bounds(bounds).max(1.0);
-                        state.selection_start = None;
-                        state.selection_end = None;
-                        return Some(Action::publish(Message::MiniTimelineZoomTo {
-                            start_fraction,
-                            end_fraction,
-                            viewport_width,
-                        }));
-                    }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Right)) if state.selecting => {
+                state.selecting = false;
+                if let Some(selection) = self.selection_bounds(state, bounds)
+                    && selection.width >= 4.0
+                {
+                    let events_width = bounds.width;
+                    let start_fraction = (selection.x / events_width).clamp(0.0, 1.0);
+                    let end_fraction =
+                        ((selection.x + selection.width) / events_width).clamp(0.0, 1.0);
+                    let viewport_width = self.viewport_width_for_bounds(bounds).max(1.0);
                     state.selection_start = None;
                     state.selection_end = None;
+                    return Some(Action::publish(Message::MiniTimelineZoomTo {
+                        start_fraction,
+                        end_fraction,
+                        viewport_width,
+                    }));
                 }
+                state.selection_start = None;
+                state.selection_end = None;
             }
             _ => {}
         }
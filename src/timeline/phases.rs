@@ -0,0 +1,174 @@
+// Track under the header showing the compiler phases as coloured bands.
+use super::format_duration;
+use crate::Message;
+use crate::analysis::phases::{PhaseBand, Phases};
+use crate::ui::shade;
+use iced::mouse;
+use iced::widget::canvas::{self, Action, Geometry, Program};
+use iced::{Color, Event, Point, Rectangle, Renderer, Theme};
+
+pub(crate) const PHASE_LANE_HEIGHT: f64 = 18.0;
+
+/// Band colours, assigned by phase index.
+const PHASE_COLORS: [Color; 8] = [
+    Color::from_rgb(0.55, 0.75, 0.95),
+    Color::from_rgb(0.65, 0.85, 0.6),
+    Color::from_rgb(0.95, 0.8, 0.5),
+    Color::from_rgb(0.9, 0.6, 0.55),
+    Color::from_rgb(0.75, 0.65, 0.9),
+    Color::from_rgb(0.6, 0.85, 0.85),
+    Color::from_rgb(0.9, 0.7, 0.85),
+    Color::from_rgb(0.8, 0.8, 0.6),
+];
+
+pub(crate) struct PhasesProgram<'a> {
+    pub(crate) phases: &'a Phases,
+    pub(crate) min_ns: u64,
+    pub(crate) zoom_level: f64,
+    pub(crate) scroll_offset_x: f64,
+}
+
+#[derive(Default)]
+pub(crate) struct PhasesState {
+    hovered_band: Option<usize>,
+}
+
+impl PhasesProgram<'_> {
+    fn band_rect(&self, band: &PhaseBand) -> Rectangle {
+        let zoom_level = self.zoom_level.max(1e-9);
+        let x = |ns: u64| {
+            ((ns.saturating_sub(self.min_ns) as f64 - self.scroll_offset_x) * zoom_level) as f32
+        };
+        let x0 = x(band.start_ns);
+        Rectangle {
+            x: x0,
+            y: (band.lane as f64 * PHASE_LANE_HEIGHT) as f32 + 1.0,
+            width: (x(band.end_ns) - x0).max(1.0),
+            height: PHASE_LANE_HEIGHT as f32 - 2.0,
+        }
+    }
+
+    fn band_at(&self, bounds: Rectangle, cursor: mouse::Cursor) -> Option<usize> {
+        let position = cursor.position_in(bounds)?;
+        self.phases
+            .bands
+            .iter()
+            .position(|band| self.band_rect(band).contains(position))
+    }
+}
+
+impl Program<Message> for PhasesProgram<'_> {
+    type State = PhasesState;
+
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<Action<Message>> {
+        match event {
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                let hovered = self.band_at(bounds, cursor);
+                if hovered != state.hovered_band {
+                    state.hovered_band = hovered;
+                    return Some(Action::request_redraw());
+                }
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let band = &self.phases.bands[self.band_at(bounds, cursor)?];
+                return Some(
+                    Action::publish(Message::TimelineZoomTo {
+                        start_ns: band.start_ns.saturating_sub(self.min_ns) as f64,
+                        end_ns: band.end_ns.saturating_sub(self.min_ns) as f64,
+                    })
+                    .and_capture(),
+                );
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn draw(
+        &self,
+        state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        frame.fill_rectangle(Point::ORIGIN, bounds.size(), shade(theme, 0.0));
+
+        for (index, band) in self.phases.bands.iter().enumerate() {
+            let rect = self.band_rect(band);
+            if rect.x > bounds.width || rect.x + rect.width < 0.0 {
+                continue;
+            }
+            let color = PHASE_COLORS[band.phase % PHASE_COLORS.len()];
+            frame.fill_rectangle(rect.position(), rect.size(), color);
+            let (border_color, border_width) = if state.hovered_band == Some(index) {
+                (Color::from_rgb(0.0, 0.4, 0.8), 2.0)
+            } else {
+                (Color::from_rgba(0.0, 0.0, 0.0, 0.3), 1.0)
+            };
+            frame.stroke(
+                &canvas::Path::rectangle(rect.position(), rect.size()),
+                canvas::Stroke::default()
+                    .with_color(border_color)
+                    .with_width(border_width),
+            );
+
+            // Keep the text inside the visible part of the band.
+            let visible_x0 = rect.x.max(0.0);
+            let visible_x1 = (rect.x + rect.width).min(bounds.width);
+            if visible_x1 - visible_x0 > 20.0 {
+                let clip = Rectangle {
+                    x: visible_x0,
+                    y: rect.y,
+                    width: visible_x1 - visible_x0,
+                    height: rect.height,
+                };
+                frame.with_clip(clip, |frame| {
+                    frame.fill_text(canvas::Text {
+                        content: format!(
+                            "{} {}",
+                            self.phases.names[band.phase],
+                            format_duration(band.end_ns.saturating_sub(band.start_ns))
+                        ),
+                        position: Point::new(visible_x0 + 3.0, rect.y + 1.0),
+                        color: Color::from_rgb(0.15, 0.15, 0.15),
+                        size: 11.0.into(),
+                        ..Default::default()
+                    });
+                });
+            }
+        }
+
+        frame.stroke(
+            &canvas::Path::line(
+                Point::new(0.0, bounds.height - 0.5),
+                Point::new(bounds.width, bounds.height - 0.5),
+            ),
+            canvas::Stroke::default()
+                .with_color(shade(theme, 0.15))
+                .with_width(1.0),
+        );
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        state: &Self::State,
+        _bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        if state.hovered_band.is_some() {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
@@ -1,30 +1,111 @@
 // Threads panel receives explicit scroll offsets from the app state (f64)
 use crate::Message;
-use crate::data::{ThreadGroup, thread_group_key};
-use crate::timeline::{LANE_HEIGHT, LANE_SPACING, group_total_height};
+use crate::analysis::thread_summary::{SPARKLINE_BUCKETS, ThreadSummary};
+use crate::data::{
+    ThreadGroup, ThreadGroupKey, TimelineEvent, thread_group_key, thread_groups_overlap,
+    thread_name,
+};
+use crate::timeline::{
+    DRAG_THRESHOLD, LANE_SPACING, MIN_TEXT_LANE_HEIGHT, format_duration, group_total_height,
+};
+use crate::ui::shade;
 use iced::mouse;
 use iced::widget::canvas::{self, Action, Geometry, Program};
 use iced::{Color, Event, Point, Rectangle, Renderer, Size, Theme};
+use std::collections::HashMap;
+
+/// Width of the area at the right edge that resizes the column.
+const SPLITTER_GRAB_WIDTH: f32 = 5.0;
+const SPARKLINE_WIDTH: f32 = 40.0;
+/// Labels narrower than this leave out the sparkline.
+const MIN_SPARKLINE_LABEL_WIDTH: f32 = 120.0;
+/// Size of the pin and hide buttons shown on the hovered label.
+const LABEL_BUTTON_SIZE: f32 = 16.0;
+const PIN_ICON: char = '\u{f10d}';
+const HIDE_ICON: char = '\u{e8f5}';
+const RENAME_ICON: char = '\u{e3c9}';
+const SPLIT_ICON: char = '\u{e0b6}';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LabelButton {
+    Rename,
+    Split,
+    Pin,
+    Hide,
+}
+
+impl LabelButton {
+    // Buttons of a group from left to right. Only single threads can be
+    // renamed from their label, and only groups merged by hand split.
+    fn of_group(group: &ThreadGroup, manual_merge: bool) -> &'static [LabelButton] {
+        if group.threads.len() == 1 {
+            &[LabelButton::Rename, LabelButton::Pin, LabelButton::Hide]
+        } else if manual_merge {
+            &[LabelButton::Split, LabelButton::Pin, LabelButton::Hide]
+        } else {
+            &[LabelButton::Pin, LabelButton::Hide]
+        }
+    }
+
+    fn icon(self) -> char {
+        match self {
+            LabelButton::Rename => RENAME_ICON,
+            LabelButton::Split => SPLIT_ICON,
+            LabelButton::Pin => PIN_ICON,
+            LabelButton::Hide => HIDE_ICON,
+        }
+    }
+}
+
+/// Where a dragged group goes when released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GroupDrop {
+    /// In front of a group, or at the end for `None`.
+    Before(Option<ThreadGroupKey>),
+    /// Merged into a group.
+    Onto(ThreadGroupKey),
+}
 
 pub(crate) struct ThreadsProgram<'a> {
+    pub(crate) events: &'a [TimelineEvent],
     pub(crate) thread_groups: &'a [ThreadGroup],
+    pub(crate) summaries: &'a HashMap<u32, ThreadSummary>,
+    pub(crate) thread_names: &'a HashMap<u32, String>,
+    /// Dropping a group onto the middle of another merges them.
+    pub(crate) manual_merge: bool,
     pub(crate) scroll_offset_y: f64,
+    pub(crate) lane_height: f64,
 }
 
 #[derive(Default)]
 pub(crate) struct ThreadsState {
-    hovered_group: Option<usize>,
+    hovered_group: Option<ThreadGroupKey>,
+    hovered_splitter: bool,
+    /// Cursor x and column width when the splitter drag started.
+    resizing: Option<(f32, f32)>,
+    /// Group whose label the left button went down on, and where.
+    pressed: Option<(ThreadGroupKey, Point)>,
+    /// Where the group being dragged would go.
+    drop: Option<GroupDrop>,
 }
 
 impl<'a> ThreadsProgram<'a> {
-    fn group_at(&self, position: Point) -> Option<usize> {
+    // Height of the clickable label row at the top of a group. It grows past
+    // thin lanes so the label stays readable, but not into the next group.
+    fn label_row_height(&self, group: &ThreadGroup) -> f64 {
+        (self.lane_height + 2.0)
+            .max(MIN_TEXT_LANE_HEIGHT)
+            .min(group_total_height(group, self.lane_height) + LANE_SPACING)
+    }
+
+    fn group_at(&self, position: Point) -> Option<ThreadGroupKey> {
         let mut y_offset: f64 = 0.0;
         let content_y = position.y as f64 + self.scroll_offset_y;
 
         for group in self.thread_groups {
-            let lane_total_height = group_total_height(group);
+            let lane_total_height = group_total_height(group, self.lane_height);
 
-            if content_y >= y_offset && content_y < y_offset + LANE_HEIGHT + 2.0 {
+            if content_y >= y_offset && content_y < y_offset + self.label_row_height(group) {
                 return Some(thread_group_key(group));
             }
 
@@ -33,6 +114,78 @@ impl<'a> ThreadsProgram<'a> {
 
         None
     }
+
+    fn find_group(&self, key: ThreadGroupKey) -> Option<&ThreadGroup> {
+        self.thread_groups
+            .iter()
+            .find(|group| thread_group_key(group) == key)
+    }
+
+    // Where `dragged` goes when released at `position`: in front of the group
+    // whose upper half is below it, or, when merging by hand, onto the group
+    // whose middle half it is over if their threads do not overlap in time.
+    fn drop_target(&self, position: Point, dragged: ThreadGroupKey) -> GroupDrop {
+        let mut y_offset: f64 = 0.0;
+        let content_y = position.y as f64 + self.scroll_offset_y;
+        let dragged = self.find_group(dragged);
+
+        for group in self.thread_groups {
+            let lane_total_height = group_total_height(group, self.lane_height);
+            let key = thread_group_key(group);
+            let in_middle = content_y >= y_offset + lane_total_height / 4.0
+                && content_y < y_offset + lane_total_height * 3.0 / 4.0;
+            if self.manual_merge
+                && in_middle
+                && let Some(dragged) = dragged
+                && thread_group_key(dragged) != key
+                && !thread_groups_overlap(self.events, dragged, group)
+            {
+                return GroupDrop::Onto(key);
+            }
+            if content_y < y_offset + lane_total_height / 2.0 {
+                return GroupDrop::Before(Some(key));
+            }
+            y_offset += lane_total_height + LANE_SPACING;
+        }
+
+        GroupDrop::Before(None)
+    }
+
+    // Content y of the top of `before`, or of the end of the list.
+    fn drop_y(&self, before: Option<ThreadGroupKey>) -> f64 {
+        let mut y_offset: f64 = 0.0;
+        for group in self.thread_groups {
+            if before == Some(thread_group_key(group)) {
+                break;
+            }
+            y_offset += group_total_height(group, self.lane_height) + LANE_SPACING;
+        }
+        y_offset
+    }
+
+    fn on_splitter(bounds: Rectangle, position: Point) -> bool {
+        position.x >= bounds.width - SPLITTER_GRAB_WIDTH
+    }
+
+    // Left edge of the `index`th of `count` label buttons, right-aligned
+    // before the splitter.
+    fn button_x(bounds: Rectangle, index: usize, count: usize) -> f32 {
+        let from_right = (count - index) as f32;
+        bounds.width - SPLITTER_GRAB_WIDTH - from_right * (LABEL_BUTTON_SIZE + 2.0)
+    }
+
+    fn button_at(
+        &self,
+        bounds: Rectangle,
+        group: &ThreadGroup,
+        position: Point,
+    ) -> Option<LabelButton> {
+        let buttons = LabelButton::of_group(group, self.manual_merge);
+        buttons.iter().enumerate().find_map(|(index, &button)| {
+            let x = Self::button_x(bounds, index, buttons.len());
+            (position.x >= x && position.x < x + LABEL_BUTTON_SIZE).then_some(button)
+        })
+    }
 }
 
 impl<'a> Program<Message> for ThreadsProgram<'a> {
@@ -42,7 +195,7 @@ impl<'a> Program<Message> for ThreadsProgram<'a> {
         &self,
         state: &Self::State,
         renderer: &Renderer,
-        _theme: &Theme,
+        theme: &Theme,
         bounds: Rectangle,
         _cursor: mouse::Cursor,
     ) -> Vec<Geometry> {
@@ -51,36 +204,64 @@ impl<'a> Program<Message> for ThreadsProgram<'a> {
         frame.fill_rectangle(
             Point::new(0.0, 0.0),
             Size::new(bounds.width, bounds.height),
-            Color::from_rgb(0.98, 0.98, 0.98),
+            shade(theme, 0.02),
         );
 
         let mut y_offset: f64 = 0.0;
         for group in self.thread_groups {
-            let lane_total_height = group_total_height(group);
+            let lane_total_height = group_total_height(group, self.lane_height);
 
             let y = (y_offset - self.scroll_offset_y) as f32;
             let row_top = y;
-            let is_hovered = state.hovered_group == Some(thread_group_key(group));
-            if is_hovered {
+            let label_row_height = self.label_row_height(group);
+            let is_dragged = state.drop.is_some()
+                && state.pressed.map(|(key, _)| key) == Some(thread_group_key(group));
+            let is_hovered = is_dragged
+                || (state.drop.is_none() && state.hovered_group == Some(thread_group_key(group)));
+            if state.drop == Some(GroupDrop::Onto(thread_group_key(group))) {
+                let target = Rectangle::new(
+                    Point::new(0.0, row_top),
+                    Size::new(bounds.width, lane_total_height as f32),
+                );
+                frame.fill_rectangle(
+                    target.position(),
+                    target.size(),
+                    Color {
+                        a: 0.15,
+                        ..theme.palette().primary
+                    },
+                );
+                frame.stroke(
+                    &canvas::Path::rectangle(target.position(), target.size()),
+                    canvas::Stroke::default()
+                        .with_color(theme.palette().primary)
+                        .with_width(2.0),
+                );
+            } else if is_hovered {
                 frame.fill_rectangle(
                     Point::new(0.0, row_top),
-                    Size::new(bounds.width, (LANE_HEIGHT + 2.0) as f32),
-                    Color::from_rgb(0.95, 0.95, 0.95),
+                    Size::new(bounds.width, label_row_height as f32),
+                    shade(theme, 0.05),
                 );
             }
 
             frame.stroke(
                 &canvas::Path::line(Point::new(0.0, row_top), Point::new(bounds.width, row_top)),
                 canvas::Stroke::default()
-                    .with_color(Color::from_rgb(0.92, 0.92, 0.92))
+                    .with_color(shade(theme, 0.08))
                     .with_width(1.0),
             );
 
+            if label_row_height < MIN_TEXT_LANE_HEIGHT {
+                y_offset += lane_total_height + LANE_SPACING;
+                continue;
+            }
+
             let icon = if group.is_collapsed { "▶" } else { "▼" };
             let icon_color = if is_hovered {
-                Color::from_rgb(0.25, 0.25, 0.25)
+                shade(theme, 0.75)
             } else {
-                Color::from_rgb(0.5, 0.5, 0.5)
+                shade(theme, 0.5)
             };
 
             frame.fill_text(canvas::Text {
@@ -91,21 +272,98 @@ impl<'a> Program<Message> for ThreadsProgram<'a> {
                 ..Default::default()
             });
 
-            frame.fill_text(canvas::Text {
-                content: group_label(group),
-                position: Point::new(22.0, row_top + 3.0),
-                color: if is_hovered {
-                    Color::from_rgb(0.1, 0.1, 0.1)
-                } else {
-                    Color::from_rgb(0.3, 0.3, 0.3)
+            let summary = ThreadSummary::of_group(self.summaries, group);
+            let mut text_right = bounds.width - 6.0;
+            if is_hovered && !is_dragged {
+                // The buttons take the place of the sparkline while hovered.
+                let buttons = LabelButton::of_group(group, self.manual_merge);
+                for (index, &button) in buttons.iter().enumerate() {
+                    let active = button == LabelButton::Pin && group.is_pinned;
+                    let x = Self::button_x(bounds, index, buttons.len());
+                    frame.fill_text(canvas::Text {
+                        content: button.icon().to_string(),
+                        position: Point::new(x, row_top + 2.0),
+                        color: if active {
+                            theme.palette().primary
+                        } else {
+                            shade(theme, 0.6)
+                        },
+                        size: 14.0.into(),
+                        font: crate::ICON_FONT,
+                        ..Default::default()
+                    });
+                }
+                text_right = Self::button_x(bounds, 0, buttons.len()) - 4.0;
+            } else if bounds.width >= MIN_SPARKLINE_LABEL_WIDTH {
+                let spark_height = (label_row_height as f32 - 6.0).max(4.0);
+                let spark_origin = Point::new(bounds.width - SPARKLINE_WIDTH - 8.0, row_top + 3.0);
+                draw_sparkline(&mut frame, theme, spark_origin, spark_height, &summary);
+                text_right = spark_origin.x - 4.0;
+            }
+
+            frame.with_clip(
+                Rectangle::new(
+                    Point::new(0.0, row_top),
+                    Size::new(text_right.max(0.0), lane_total_height as f32),
+                ),
+                |frame| {
+                    frame.fill_text(canvas::Text {
+                        content: group_label(group, self.thread_names),
+                        position: Point::new(22.0, row_top + 3.0),
+                        color: if is_hovered {
+                            shade(theme, 0.9)
+                        } else {
+                            shade(theme, 0.7)
+                        },
+                        size: 12.0.into(),
+                        ..Default::default()
+                    });
+
+                    // Totals go on a second line when the group has room.
+                    if lane_total_height >= label_row_height + 12.0 {
+                        frame.fill_text(canvas::Text {
+                            content: format!(
+                                "{} events · {}",
+                                summary.event_count,
+                                format_duration(summary.busy_ns)
+                            ),
+                            position: Point::new(22.0, row_top + label_row_height as f32),
+                            color: shade(theme, 0.5),
+                            size: 10.0.into(),
+                            ..Default::default()
+                        });
+                    }
                 },
-                size: 12.0.into(),
-                ..Default::default()
-            });
+            );
 
             y_offset += lane_total_height + LANE_SPACING;
         }
 
+        if let Some(GroupDrop::Before(before)) = state.drop {
+            let y = (self.drop_y(before) - self.scroll_offset_y) as f32;
+            frame.stroke(
+                &canvas::Path::line(Point::new(0.0, y), Point::new(bounds.width, y)),
+                canvas::Stroke::default()
+                    .with_color(theme.palette().primary)
+                    .with_width(2.0),
+            );
+        }
+
+        let splitter_active = state.hovered_splitter || state.resizing.is_some();
+        frame.stroke(
+            &canvas::Path::line(
+                Point::new(bounds.width - 0.5, 0.0),
+                Point::new(bounds.width - 0.5, bounds.height),
+            ),
+            canvas::Stroke::default()
+                .with_color(if splitter_active {
+                    theme.palette().primary
+                } else {
+                    shade(theme, 0.15)
+                })
+                .with_width(if splitter_active { 2.0 } else { 1.0 }),
+        );
+
         vec![frame.into_geometry()]
     }
 
@@ -116,21 +374,93 @@ impl<'a> Program<Message> for ThreadsProgram<'a> {
         bounds: Rectangle,
         cursor: mouse::Cursor,
     ) -> Option<Action<Message>> {
-        if let Event::Mouse(mouse::Event::CursorMoved { .. }) = event {
-            let hovered = cursor
-                .position_in(bounds)
-                .and_then(|position| self.group_at(position));
-
-            if state.hovered_group != hovered {
-                state.hovered_group = hovered;
+        if let Some((start_x, start_width)) = state.resizing {
+            match event {
+                Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                    let width = start_width + (position.x - start_x);
+                    return Some(
+                        Action::publish(Message::LabelWidthChanged(width as f64)).and_capture(),
+                    );
+                }
+                Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                    state.resizing = None;
+                    return Some(Action::request_redraw().and_capture());
+                }
+                _ => return None,
             }
         }
 
-        if let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event
-            && let Some(position) = cursor.position_in(bounds)
-            && let Some(group_id) = self.group_at(position)
-        {
-            return Some(Action::publish(Message::ToggleThreadCollapse(group_id)));
+        match event {
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                let position = cursor.position_in(bounds);
+                let on_splitter =
+                    position.is_some_and(|position| Self::on_splitter(bounds, position));
+                let hovered = position.and_then(|position| self.group_at(position));
+                let mut changed =
+                    state.hovered_splitter != on_splitter || state.hovered_group != hovered;
+                state.hovered_splitter = on_splitter;
+                state.hovered_group = hovered;
+
+                // Start dragging the pressed group once the cursor moved far
+                // enough, then follow it with the drop marker.
+                if let Some((pressed, pressed_at)) = state.pressed
+                    && let Some(position) = cursor.position_in(bounds)
+                {
+                    let dragging = state.drop.is_some()
+                        || (position.y - pressed_at.y).abs() as f64 > DRAG_THRESHOLD;
+                    if dragging {
+                        let drop = Some(self.drop_target(position, pressed));
+                        changed |= state.drop != drop;
+                        state.drop = drop;
+                    }
+                }
+
+                if changed {
+                    return Some(Action::request_redraw());
+                }
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let position = cursor.position_in(bounds)?;
+                if Self::on_splitter(bounds, position)
+                    && let Some(absolute) = cursor.position()
+                {
+                    state.resizing = Some((absolute.x, bounds.width));
+                    return Some(Action::request_redraw().and_capture());
+                }
+                let group_id = self.group_at(position)?;
+                let group = self.find_group(group_id)?;
+                let message = match self.button_at(bounds, group, position) {
+                    Some(LabelButton::Rename) => Message::RenameThread(group.threads[0].thread_id),
+                    Some(LabelButton::Split) => Message::SplitThreadGroup(group_id),
+                    Some(LabelButton::Pin) => Message::ToggleThreadGroupPinned(group_id),
+                    Some(LabelButton::Hide) => Message::HideThreadGroup(group_id),
+                    None => {
+                        state.pressed = Some((group_id, position));
+                        return Some(Action::capture());
+                    }
+                };
+                return Some(Action::publish(message).and_capture());
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                let (group_id, _) = state.pressed.take()?;
+                let message = match state.drop.take() {
+                    // Dropping a group in front of itself leaves it in place.
+                    Some(GroupDrop::Before(before)) if before == Some(group_id) => {
+                        return Some(Action::request_redraw());
+                    }
+                    Some(GroupDrop::Before(before)) => Message::MoveThreadGroup {
+                        group: group_id,
+                        before,
+                    },
+                    Some(GroupDrop::Onto(into)) => Message::MergeThreadGroups {
+                        group: group_id,
+                        into,
+                    },
+                    None => Message::ToggleThreadCollapse(group_id),
+                };
+                return Some(Action::publish(message).and_capture());
+            }
+            _ => {}
         }
 
         None
@@ -142,7 +472,11 @@ impl<'a> Program<Message> for ThreadsProgram<'a> {
         _bounds: Rectangle,
         _cursor: mouse::Cursor,
     ) -> mouse::Interaction {
-        if state.hovered_group.is_some() {
+        if state.resizing.is_some() || state.hovered_splitter {
+            mouse::Interaction::ResizingHorizontally
+        } else if state.drop.is_some() {
+            mouse::Interaction::Grabbing
+        } else if state.hovered_group.is_some() {
             mouse::Interaction::Pointer
         } else {
             mouse::Interaction::default()
@@ -150,14 +484,53 @@ impl<'a> Program<Message> for ThreadsProgram<'a> {
     }
 }
 
-fn group_label(group: &ThreadGroup) -> String {
-    // For a single-thread group use the concise form "Thread <id>".
+fn group_label(group: &ThreadGroup, thread_names: &HashMap<u32, String>) -> String {
+    // For a single-thread group use its name, or "Thread <id>".
     if group.threads.len() == 1
         && let Some(thread) = group.threads.first()
     {
-        return format!("Thread {}", thread.thread_id);
+        return thread_name(thread_names, thread.thread_id);
     }
 
-    // For multi-thread groups display a concise "Merged" label.
-    "Merged".to_string()
+    // Merged groups list the names or ids of the threads they contain.
+    let threads: Vec<String> = group
+        .threads
+        .iter()
+        .map(|thread| match thread_names.get(&thread.thread_id) {
+            Some(name) => name.clone(),
+            None => thread.thread_id.to_string(),
+        })
+        .collect();
+    format!("Merged {}", threads.join(", "))
+}
+
+// Busy fraction over the profile as small bars, one per bucket.
+fn draw_sparkline(
+    frame: &mut canvas::Frame,
+    theme: &Theme,
+    origin: Point,
+    height: f32,
+    summary: &ThreadSummary,
+) {
+    frame.fill_rectangle(
+        origin,
+        Size::new(SPARKLINE_WIDTH, height),
+        shade(theme, 0.05),
+    );
+    let bar_width = SPARKLINE_WIDTH / SPARKLINE_BUCKETS as f32;
+    let color = theme.palette().primary;
+    for (bucket, value) in summary.activity.iter().enumerate() {
+        let bar_height = value.clamp(0.0, 1.0) * height;
+        if bar_height <= 0.0 {
+            continue;
+        }
+        frame.fill_rectangle(
+            Point::new(
+                origin.x + bucket as f32 * bar_width,
+                origin.y + height - bar_height,
+            ),
+            Size::new(bar_width, bar_height),
+            Color { a: 0.7, ..color },
+        );
+    }
 }
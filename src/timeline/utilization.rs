@@ -0,0 +1,97 @@
+// Track under the header showing how many threads are busy over time.
+use crate::Message;
+use crate::analysis::utilization::Utilization;
+use crate::ui::shade;
+use iced::mouse;
+use iced::widget::canvas::{self, Geometry, Program};
+use iced::{Color, Point, Rectangle, Renderer, Size, Theme};
+
+pub(crate) const UTILIZATION_FILL_COLOR: Color = Color::from_rgb(0.35, 0.55, 0.75);
+
+pub(crate) struct UtilizationProgram<'a> {
+    pub(crate) utilization: &'a Utilization,
+    pub(crate) min_ns: u64,
+    pub(crate) zoom_level: f64,
+    pub(crate) scroll_offset_x: f64,
+}
+
+/// Draw the busy thread count as one bar per pixel column, scaled so
+/// `max_busy` fills `area`. `start_ns` is absolute.
+pub(crate) fn draw_utilization(
+    frame: &mut canvas::Frame,
+    utilization: &Utilization,
+    area: Rectangle,
+    start_ns: f64,
+    ns_per_pixel: f64,
+    color: Color,
+) {
+    if utilization.max_busy == 0 || area.width < 1.0 {
+        return;
+    }
+    let columns = area.width.ceil() as usize;
+    let averages = utilization.column_averages(start_ns, ns_per_pixel, columns);
+    let scale = area.height / utilization.max_busy as f32;
+    for (column, average) in averages.into_iter().enumerate() {
+        if average <= 0.0 {
+            continue;
+        }
+        let height = average * scale;
+        frame.fill_rectangle(
+            Point::new(area.x + column as f32, area.y + area.height - height),
+            Size::new(1.0, height),
+            color,
+        );
+    }
+}
+
+impl Program<Message> for UtilizationProgram<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        frame.fill_rectangle(Point::ORIGIN, bounds.size(), shade(theme, 0.0));
+
+        let zoom_level = self.zoom_level.max(1e-9);
+        let area = Rectangle {
+            x: 0.0,
+            y: 2.0,
+            width: bounds.width,
+            height: (bounds.height - 3.0).max(0.0),
+        };
+        draw_utilization(
+            &mut frame,
+            self.utilization,
+            area,
+            self.min_ns as f64 + self.scroll_offset_x.max(0.0),
+            1.0 / zoom_level,
+            UTILIZATION_FILL_COLOR,
+        );
+
+        frame.fill_text(canvas::Text {
+            content: format!("max {}", self.utilization.max_busy),
+            position: Point::new(bounds.width - 4.0, 2.0),
+            color: shade(theme, 0.6),
+            size: 10.0.into(),
+            align_x: iced::widget::text::Alignment::Right,
+            ..Default::default()
+        });
+        frame.stroke(
+            &canvas::Path::line(
+                Point::new(0.0, bounds.height - 0.5),
+                Point::new(bounds.width, bounds.height - 0.5),
+            ),
+            canvas::Stroke::default()
+                .with_color(shade(theme, 0.15))
+                .with_width(1.0),
+        );
+
+        vec![frame.into_geometry()]
+    }
+}
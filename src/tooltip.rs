@@ -9,27 +9,28 @@ use iced::{
 use iced::advanced::Overlay;
 use iced::overlay;
 
+const TOOLTIP_PADDING: f32 = 6.0;
+
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct TooltipStyle {
     pub background: Color,
     pub border: Border,
     pub shadow_color: Color,
     pub shadow_offset: Vector,
-    pub padding: f32,
 }
 
-impl Default for TooltipStyle {
-    fn default() -> Self {
+impl TooltipStyle {
+    pub fn from_theme(theme: &Theme) -> Self {
+        let palette = theme.extended_palette();
         Self {
-            background: Color::from_rgb(1.0, 1.0, 1.0),
+            background: palette.background.base.color,
             border: Border {
-                color: Color::from_rgba(0.0, 0.0, 0.0, 0.35),
+                color: palette.background.strongest.color,
                 width: 1.0,
                 radius: 4.0.into(),
             },
             shadow_color: Color::from_rgba(0.0, 0.0, 0.0, 0.15),
             shadow_offset: Vector::new(2.0, 2.0),
-            padding: 6.0,
         }
     }
 }
@@ -44,7 +45,6 @@ pub(crate) struct Tooltip<'a> {
     show: bool,
     position: Point,
     offset: Vector,
-    style: TooltipStyle,
 }
 
 impl std::fmt::Debug for Tooltip<'_> {
@@ -67,7 +67,6 @@ impl<'a> Tooltip<'a> {
             show: false,
             position: Point::ORIGIN,
             offset: Vector::new(10.0, 10.0),
-            style: TooltipStyle::default(),
         }
     }
 
@@ -217,7 +216,6 @@ impl<'a> Widget<crate::Message, Theme, Renderer> for Tooltip<'a> {
             TooltipOverlay::new(
                 self.position + translation,
                 self.offset,
-                self.style,
                 &mut tree.children[1],
                 &mut self.overlay_content,
             )
@@ -238,7 +236,6 @@ struct State;
 struct TooltipOverlay<'a, 'b> {
     anchor: Point,
     offset: Vector,
-    style: TooltipStyle,
     tree: &'b mut Tree,
     content: &'b mut Element<'a, crate::Message>,
 }
@@ -247,14 +244,12 @@ impl<'a, 'b> TooltipOverlay<'a, 'b> {
     fn new(
         anchor: Point,
         offset: Vector,
-        style: TooltipStyle,
         tree: &'b mut Tree,
         content: &'b mut Element<'a, crate::Message>,
     ) -> Self {
         Self {
             anchor,
             offset,
-            style,
             tree,
             content,
         }
@@ -274,7 +269,7 @@ impl Overlay<crate::Message, Theme, Renderer> for TooltipOverlay<'_, '_> {
             .as_widget_mut()
             .layout(self.tree, renderer, &limits);
 
-        let padding = self.style.padding;
+        let padding = TOOLTIP_PADDING;
         let background_w = content.size().width + padding * 2.0;
         let background_h = content.size().height + padding * 2.0;
 
@@ -295,12 +290,13 @@ impl Overlay<crate::Message, Theme, Renderer> for TooltipOverlay<'_, '_> {
     fn draw(
         &self,
         renderer: &mut Renderer,
-        _theme: &Theme,
-        _style: &renderer::Style,
+        theme: &Theme,
+        style: &renderer::Style,
         layout: Layout<'_>,
         _cursor: mouse::Cursor,
     ) {
-        let padding = self.style.padding;
+        let padding = TOOLTIP_PADDING;
+        let tooltip_style = TooltipStyle::from_theme(theme);
         let Some(content_layout) = layout.children().next() else {
             return;
         };
@@ -315,8 +311,8 @@ impl Overlay<crate::Message, Theme, Renderer> for TooltipOverlay<'_, '_> {
         };
 
         let shadow_bounds = Rectangle {
-            x: background_bounds.x + self.style.shadow_offset.x,
-            y: background_bounds.y + self.style.shadow_offset.y,
+            x: background_bounds.x + tooltip_style.shadow_offset.x,
+            y: background_bounds.y + tooltip_style.shadow_offset.y,
             width: background_bounds.width,
             height: background_bounds.height,
         };
@@ -325,29 +321,29 @@ impl Overlay<crate::Message, Theme, Renderer> for TooltipOverlay<'_, '_> {
             renderer::Quad {
                 bounds: shadow_bounds,
                 border: Border {
-                    radius: self.style.border.radius,
+                    radius: tooltip_style.border.radius,
                     width: 0.0,
                     color: Color::TRANSPARENT,
                 },
                 ..Default::default()
             },
-            self.style.shadow_color,
+            tooltip_style.shadow_color,
         );
 
         renderer.fill_quad(
             renderer::Quad {
                 bounds: background_bounds,
-                border: self.style.border,
+                border: tooltip_style.border,
                 ..Default::default()
             },
-            self.style.background,
+            tooltip_style.background,
         );
 
         self.content.as_widget().draw(
             self.tree,
             renderer,
-            _theme,
-            _style,
+            theme,
+            style,
             content_layout,
             mouse::Cursor::Unavailable,
             &layout.bounds(),
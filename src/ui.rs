@@ -1,5 +1,7 @@
-use iced::Theme;
-use iced::widget::button;
+use crate::Message;
+use crate::data::EventId;
+use iced::widget::{button, container, text};
+use iced::{Color, Element, Length, Theme};
 
 pub fn neutral_button_style(theme: &Theme, status: button::Status) -> button::Style {
     let palette = theme.extended_palette();
@@ -15,3 +17,69 @@ pub fn neutral_button_style(theme: &Theme, status: button::Status) -> button::St
         _ => base,
     }
 }
+
+// Borderless button used for the timeline toolbar actions. Disabled buttons
+// fade their text so unavailable actions (e.g. an empty history) read as such.
+pub fn toolbar_button_style(theme: &Theme, status: button::Status) -> button::Style {
+    let palette = theme.extended_palette();
+    let base = button::Style {
+        text_color: palette.background.base.text,
+        ..Default::default()
+    };
+    match status {
+        button::Status::Hovered | button::Status::Pressed => button::Style {
+            background: Some(palette.background.weak.color.into()),
+            ..base
+        },
+        button::Status::Disabled => button::Style {
+            text_color: palette.background.strong.color,
+            ..base
+        },
+        button::Status::Active => base,
+    }
+}
+
+// Background of the analysis views and other panels: the theme's base color
+// with a subtle strong-color border, so the panel reads as one block.
+pub fn panel_style(theme: &Theme) -> container::Style {
+    let palette = theme.extended_palette();
+    container::Style::default()
+        .background(palette.background.base.color)
+        .border(iced::Border {
+            color: palette.background.strong.color,
+            width: 1.0,
+            ..Default::default()
+        })
+}
+
+// "Show" button of a list row that shows a range, given in ns relative to the
+// profile start, in the timeline and selects `event`.
+pub fn show_range_button<'a>(
+    start_ns: f64,
+    end_ns: f64,
+    event: Option<EventId>,
+) -> Element<'a, Message> {
+    button(text("Show").size(12))
+        .style(toolbar_button_style)
+        .padding(2)
+        .width(Length::Fixed(50.0))
+        .on_press(Message::ShowRangeInTimeline {
+            start_ns,
+            end_ns,
+            event,
+        })
+        .into()
+}
+
+// A grey between the theme's background (0.0) and text (1.0) colors. Canvas
+// programs draw backgrounds, grid lines and secondary text with these so they
+// follow the selected theme.
+pub fn shade(theme: &Theme, amount: f32) -> Color {
+    let palette = theme.palette();
+    let (from, to) = (palette.background, palette.text);
+    Color::from_rgb(
+        from.r + (to.r - from.r) * amount,
+        from.g + (to.g - from.g) * amount,
+        from.b + (to.b - from.b) * amount,
+    )
+}